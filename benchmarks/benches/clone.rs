@@ -0,0 +1,21 @@
+use std::rc::Rc as StdRc;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use cactusref::Rc;
+
+fn bench_clone(c: &mut Criterion) {
+    let mut group = c.benchmark_group("clone");
+    group.bench_function("std::rc::Rc", |b| {
+        let rc = StdRc::new(0_u64);
+        b.iter_with_large_drop(|| StdRc::clone(black_box(&rc)))
+    });
+    group.bench_function("cactusref::Rc with no adoptions", |b| {
+        let rc = Rc::new(0_u64);
+        b.iter_with_large_drop(|| Rc::clone(black_box(&rc)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_clone);
+criterion_main!(benches);
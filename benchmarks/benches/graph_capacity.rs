@@ -0,0 +1,115 @@
+use std::cell::RefCell;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use cactusref::{Adopt, Rc};
+
+struct Node {
+    links: Vec<Rc<RefCell<Self>>>,
+}
+
+fn ring(count: usize, new: impl Fn() -> Rc<RefCell<Node>>) -> Rc<RefCell<Node>> {
+    let first = new();
+    let mut last = Rc::clone(&first);
+    for _ in 1..count {
+        let obj = new();
+        obj.borrow_mut().links.push(Rc::clone(&last));
+        unsafe {
+            Rc::adopt_unchecked(&obj, &last);
+        }
+        last = obj;
+    }
+    first.borrow_mut().links.push(Rc::clone(&last));
+    unsafe {
+        Rc::adopt_unchecked(&first, &last);
+    }
+    first
+}
+
+fn star(count: usize, head: Rc<RefCell<Node>>) -> Rc<RefCell<Node>> {
+    for _ in 0..count {
+        let obj = Rc::new(RefCell::new(Node { links: vec![] }));
+        unsafe {
+            Rc::adopt_unchecked(&head, &obj);
+        }
+        head.borrow_mut().links.push(obj);
+    }
+    head
+}
+
+fn bench_ring(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ring construction");
+    for count in [10, 100, 1000] {
+        group.bench_with_input(BenchmarkId::new("Rc::new", count), &count, |b, &count| {
+            b.iter_with_large_drop(|| {
+                ring(black_box(count), || {
+                    Rc::new(RefCell::new(Node { links: vec![] }))
+                })
+            });
+        });
+        // Each ring member has one forward and one backward link.
+        group.bench_with_input(
+            BenchmarkId::new("Rc::new_graphed_with_capacity", count),
+            &count,
+            |b, &count| {
+                b.iter_with_large_drop(|| {
+                    ring(black_box(count), || {
+                        Rc::new_graphed_with_capacity(RefCell::new(Node { links: vec![] }), 2)
+                    })
+                });
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("Rc::reserve_adoptions", count),
+            &count,
+            |b, &count| {
+                b.iter_with_large_drop(|| {
+                    ring(black_box(count), || {
+                        let node = Rc::new(RefCell::new(Node { links: vec![] }));
+                        Rc::reserve_adoptions(&node, 2);
+                        node
+                    })
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_star(c: &mut Criterion) {
+    let mut group = c.benchmark_group("star construction");
+    for count in [10, 100, 1000] {
+        group.bench_with_input(BenchmarkId::new("Rc::new", count), &count, |b, &count| {
+            b.iter_with_large_drop(|| {
+                let head = Rc::new(RefCell::new(Node { links: vec![] }));
+                star(black_box(count), head)
+            });
+        });
+        group.bench_with_input(
+            BenchmarkId::new("Rc::new_graphed_with_capacity", count),
+            &count,
+            |b, &count| {
+                b.iter_with_large_drop(|| {
+                    let head =
+                        Rc::new_graphed_with_capacity(RefCell::new(Node { links: vec![] }), count);
+                    star(black_box(count), head)
+                });
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("Rc::reserve_adoptions", count),
+            &count,
+            |b, &count| {
+                b.iter_with_large_drop(|| {
+                    let head = Rc::new(RefCell::new(Node { links: vec![] }));
+                    Rc::reserve_adoptions(&head, count);
+                    star(black_box(count), head)
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_ring, bench_star);
+criterion_main!(benches);
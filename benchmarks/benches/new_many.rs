@@ -0,0 +1,39 @@
+use std::cell::RefCell;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use cactusref::Rc;
+
+type Node = RefCell<Vec<usize>>;
+
+fn per_node_loop(count: usize) -> Vec<Rc<Node>> {
+    let mut nodes = vec![];
+    for _ in 0..count {
+        nodes.push(Rc::new(RefCell::new(vec![])));
+    }
+    nodes
+}
+
+fn new_many(count: usize) -> Vec<Rc<Node>> {
+    Rc::new_many((0..count).map(|_| RefCell::new(vec![])))
+}
+
+fn bench_new_many(c: &mut Criterion) {
+    let mut group = c.benchmark_group("graph node construction");
+    for count in [10, 100, 1000] {
+        group.bench_with_input(
+            BenchmarkId::new("per-node loop", count),
+            &count,
+            |b, &count| b.iter_with_large_drop(|| per_node_loop(black_box(count))),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("Rc::new_many", count),
+            &count,
+            |b, &count| b.iter_with_large_drop(|| new_many(black_box(count))),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_new_many);
+criterion_main!(benches);
@@ -0,0 +1,46 @@
+use std::cell::RefCell;
+use std::rc::Rc as StdRc;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use cactusref::Rc;
+
+struct Node {
+    next: RefCell<Option<Rc<Node>>>,
+}
+
+// `Weak::upgrade` only checks the strong count, so all three benchmarks are
+// expected to report the same time within noise.
+//
+// The cycle is kept small because dropping the upgraded `Rc`s traces the
+// object graph. That work is not timed, but it evicts the cache for large
+// graphs.
+fn bench_upgrade(c: &mut Criterion) {
+    let mut group = c.benchmark_group("upgrade");
+    group.bench_function("std::rc::Weak", |b| {
+        let rc = StdRc::new(0_u64);
+        let weak = StdRc::downgrade(&rc);
+        b.iter_with_large_drop(|| black_box(&weak).upgrade())
+    });
+    group.bench_function("cactusref::Weak with no adoptions", |b| {
+        let rc = Rc::new(0_u64);
+        let weak = Rc::downgrade(&rc);
+        b.iter_with_large_drop(|| black_box(&weak).upgrade())
+    });
+    group.bench_function("cactusref::Weak to a member of an adopted cycle", |b| {
+        let nodes = Rc::new_many((0..2).map(|_| Node {
+            next: RefCell::new(None),
+        }));
+        for (idx, node) in nodes.iter().enumerate() {
+            let next = &nodes[(idx + 1) % nodes.len()];
+            let next = unsafe { Rc::adopt_and_keep(node, next) };
+            *node.next.borrow_mut() = Some(next);
+        }
+        let weak = Rc::downgrade(&nodes[0]);
+        b.iter_with_large_drop(|| black_box(&weak).upgrade())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_upgrade);
+criterion_main!(benches);
@@ -0,0 +1,37 @@
+#![no_main]
+
+use cactusref::testing::{apply_ops, CountingAllocator, Op};
+use libfuzzer_sys::fuzz_target;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+// Each op is encoded as a tag byte followed by one byte per operand:
+//
+// - `tag % 5 == 0`: `Op::NewNode`
+// - `tag % 5 == 1`: `Op::Clone(a)`
+// - `tag % 5 == 2`: `Op::Adopt(a, b)`
+// - `tag % 5 == 3`: `Op::Unadopt(a, b)`
+// - `tag % 5 == 4`: `Op::Drop(a)`
+//
+// Missing operands at the end of the input are 0.
+fn decode(data: &[u8]) -> Vec<Op> {
+    let mut bytes = data.iter().copied().map(usize::from);
+    let mut ops = vec![];
+    while let Some(tag) = bytes.next() {
+        let mut operand = || bytes.next().unwrap_or_default();
+        let op = match tag % 5 {
+            0 => Op::NewNode,
+            1 => Op::Clone(operand()),
+            2 => Op::Adopt(operand(), operand()),
+            3 => Op::Unadopt(operand(), operand()),
+            _ => Op::Drop(operand()),
+        };
+        ops.push(op);
+    }
+    ops
+}
+
+fuzz_target!(|data: &[u8]| {
+    apply_ops(&decode(data));
+});
@@ -1,7 +1,15 @@
+use alloc::vec::Vec;
 use core::ptr;
 
-use crate::link::Link;
-use crate::Rc;
+#[cfg(feature = "std")]
+use crate::config::max_graph_size;
+#[cfg(feature = "std")]
+use crate::cycle::{graph_edges, graph_nodes};
+#[cfg(feature = "std")]
+use crate::error::GraphTooLarge;
+use crate::link::{Kind, Link};
+use crate::rc::RcInnerPtr;
+use crate::{Rc, Weak};
 
 mod sealed {
     use crate::Rc;
@@ -18,6 +26,33 @@ mod sealed {
 /// Calling [`adopt_unchecked`] builds an object graph which can be used by to
 /// detect cycles.
 ///
+/// # Strong Counts
+///
+/// Adoption is pure bookkeeping. Neither [`adopt_unchecked`] nor [`unadopt`]
+/// modify the strong count of either `Rc`. An adoption records that `this`
+/// already owns a clone of `other`, so callers are expected to pair each call
+/// to `adopt_unchecked` with a call to [`Rc::clone`] whose result is stored in
+/// `this`, and each call to `unadopt` with dropping that clone.
+///
+/// ```
+/// use cactusref::{Adopt, Rc};
+///
+/// let node = Rc::new(());
+/// let clone = Rc::clone(&node);
+/// assert_eq!(Rc::strong_count(&node), 2);
+///
+/// unsafe {
+///     Rc::adopt_unchecked(&node, &clone);
+/// }
+/// assert_eq!(Rc::strong_count(&node), 2);
+///
+/// Rc::unadopt(&node, &clone);
+/// assert_eq!(Rc::strong_count(&node), 2);
+///
+/// drop(clone);
+/// assert_eq!(Rc::strong_count(&node), 1);
+/// ```
+///
 /// # Safety
 ///
 /// Implementors of this trait must ensure that bookkeeping edges in the object
@@ -32,6 +67,7 @@ mod sealed {
 /// - Dangling `Rc`s which will cause a use after free.
 ///
 /// [`adopt_unchecked`]: Adopt::adopt_unchecked
+/// [`unadopt`]: Adopt::unadopt
 /// [undefined behavior]: https://doc.rust-lang.org/reference/behavior-considered-undefined.html
 pub unsafe trait Adopt: sealed::Sealed {
     /// Perform bookkeeping to record that `this` has an owned reference to
@@ -55,6 +91,16 @@ pub unsafe trait Adopt: sealed::Sealed {
     /// reference to `other` to avoid memory leaks, but this is not required for
     /// soundness.
     ///
+    /// Calling `adopt_unchecked` does not increment the strong count of
+    /// `other`. Callers must ensure that `other` has been cloned and stored in
+    /// the `T` contained by `this`.
+    ///
+    /// # Collection
+    ///
+    /// Calls to `adopt_unchecked` made while either `Rc` is being deallocated,
+    /// for example from a [`Drop`] implementation of a value in a cycle that
+    /// is being collected, are ignored.
+    ///
     /// [`unadopt`]: Adopt::unadopt
     unsafe fn adopt_unchecked(this: &Self, other: &Self);
 
@@ -68,10 +114,24 @@ pub unsafe trait Adopt: sealed::Sealed {
     /// `Adopt::unadopt(...)`. A method would interfere with methods of the same
     /// name on the contents of a `Rc` used through `Deref`.
     ///
+    /// Calling `unadopt` does not decrement the strong count of `other`.
+    /// Callers are responsible for dropping the clone of `other` that was
+    /// removed from `this`.
+    ///
     /// # Memory Leaks
     ///
     /// Failure to call this function when removing an owned `Rc` from `this`
     /// is safe, but may result in a memory leak.
+    ///
+    /// Calling `unadopt` when `this` has not adopted `other` has no effect. In
+    /// builds with debug assertions enabled, this logs a warning. Use
+    /// [`Rc::try_unadopt`] to detect these calls.
+    ///
+    /// # Collection
+    ///
+    /// Calls to `unadopt` made while either `Rc` is being deallocated, for
+    /// example from a [`Drop`] implementation of a value in a cycle that is
+    /// being collected, are ignored.
     fn unadopt(this: &Self, other: &Self);
 }
 
@@ -134,23 +194,27 @@ unsafe impl<T> Adopt for Rc<T> {
     ///
     /// [`unadopt`]: Rc::unadopt
     unsafe fn adopt_unchecked(this: &Self, other: &Self) {
+        // `T::drop` may run while a cycle is being collected. The links of dead
+        // `Rc`s have already been torn down, so ignore the bookkeeping request
+        // instead of reallocating `links`.
+        if this.inner().is_dead() || other.inner().is_dead() {
+            return;
+        }
         // Self-adoptions have no effect.
         if ptr::eq(this, other) {
             // Store a loopback reference to `other` in `this`. This bookkeeping
             // logs a strong reference and is used for discovering cycles.
             //
-            // SAFETY: `this` is a live `Rc` so the `links` on its inner
-            // allocation are an inhabited `MaybeUninit`.
-            let mut links = this.inner().links().borrow_mut();
+            // The `links` of `this` are allocated on its first adoption.
+            let mut links = this.inner().links_or_init().borrow_mut();
             links.insert(Link::loopback(other.ptr));
             return;
         }
         // Store a forward reference to `other` in `this`. This bookkeeping logs
         // a strong reference and is used for discovering cycles.
         //
-        // SAFETY: `this` is a live `Rc` so the `links` on its inner allocation
-        // are an inhabited `MaybeUninit`.
-        let mut links = this.inner().links().borrow_mut();
+        // The `links` of `this` are allocated on its first adoption.
+        let mut links = this.inner().links_or_init().borrow_mut();
         links.insert(Link::forward(other.ptr));
         // `this` and `other` may point to the same allocation. Drop the borrow
         // on `links` before accessing `other` to avoid a already borrowed error
@@ -159,9 +223,8 @@ unsafe impl<T> Adopt for Rc<T> {
         // Store a backward reference to `this` in `other`. This bookkeeping is
         // used for discovering cycles.
         //
-        // SAFETY: `this` is a live `Rc` so the `links` on its inner allocation
-        // are an inhabited `MaybeUninit`.
-        let mut links = other.inner().links().borrow_mut();
+        // The `links` of `other` are allocated on its first adoption.
+        let mut links = other.inner().links_or_init().borrow_mut();
         links.insert(Link::backward(this.ptr));
     }
 
@@ -180,6 +243,10 @@ unsafe impl<T> Adopt for Rc<T> {
     /// Failure to call this function when removing an owned `Rc` from `this`
     /// is safe, but may result in a memory leak.
     ///
+    /// Calling `unadopt` when `this` has not adopted `other` has no effect. In
+    /// builds with debug assertions enabled, this logs a warning. Use
+    /// [`Rc::try_unadopt`] to detect these calls.
+    ///
     /// # Examples
     ///
     /// The following implements a self-referential array.
@@ -215,34 +282,481 @@ unsafe impl<T> Adopt for Rc<T> {
     /// assert_eq!(weak.weak_count(), 0);
     /// ```
     fn unadopt(this: &Self, other: &Self) {
+        let removed = Rc::try_unadopt(this, other);
+        // Unadopting more times than adopting is a bookkeeping bug in the
+        // caller. Requests made while a cycle is being collected are expected
+        // to be ignored.
+        if cfg!(debug_assertions) && !removed && !this.inner().is_dead() && !other.inner().is_dead()
+        {
+            warn!("cactusref unadopt called without a matching adoption");
+        }
+    }
+}
+
+impl<T> Rc<T> {
+    /// Perform bookkeeping to record that `this` has removed an owned reference
+    /// to `other`, returning whether a matching adoption was removed.
+    ///
+    /// This behaves like [`Adopt::unadopt`], but reports calls which have no
+    /// effect because `this` has not adopted `other`, for example when `other`
+    /// is unadopted more times than it was adopted.
+    ///
+    /// This is an associated function that needs to be used as
+    /// `Rc::try_unadopt(...)`. A method would interfere with methods of the
+    /// same name on the contents of a `Rc` used through `Deref`.
+    ///
+    /// Calls made while either `Rc` is being deallocated, for example from a
+    /// [`Drop`] implementation of a value in a cycle that is being collected,
+    /// are ignored and return `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cactusref::{Adopt, Rc};
+    ///
+    /// let first = Rc::new(1);
+    /// let second = Rc::new(2);
+    /// let clone = Rc::clone(&second);
+    /// unsafe {
+    ///     Rc::adopt_unchecked(&first, &clone);
+    /// }
+    ///
+    /// assert!(Rc::try_unadopt(&first, &clone));
+    /// assert!(!Rc::try_unadopt(&first, &clone));
+    /// drop(clone);
+    /// ```
+    #[allow(clippy::must_use_candidate)]
+    pub fn try_unadopt(this: &Self, other: &Self) -> bool {
+        // `T::drop` may run while a cycle is being collected. The links of dead
+        // `Rc`s have already been torn down, so ignore the bookkeeping request.
+        if this.inner().is_dead() || other.inner().is_dead() {
+            return false;
+        }
         // Self-adoptions have no effect.
         if ptr::eq(this, other) {
             // Remove a loopback reference to `other` in `this`. This bookkeeping
             // logs a strong reference and is used for discovering cycles.
             //
-            // SAFETY: `this` is a live `Rc` so the `links` on its inner
-            // allocation are an inhabited `MaybeUninit`.
-            let mut links = unsafe { this.inner().links().borrow_mut() };
-            links.remove(Link::loopback(other.ptr), 1);
-            return;
+            // If `this` has no `links`, it has never been adopted.
+            return this
+                .inner()
+                .links()
+                .is_some_and(|links| links.borrow_mut().remove(Link::loopback(other.ptr), 1));
         }
         // Remove a forward reference to `other` in `this`. This bookkeeping
         // removes a strong reference and is used for discovering cycles.
         //
-        // SAFETY: `this` is a live `Rc` so the `links` on its inner allocation
-        // are an inhabited `MaybeUninit`.
-        let mut links = unsafe { this.inner().links().borrow_mut() };
-        links.remove(Link::forward(other.ptr), 1);
         // `this` and `other` may point to the same allocation. Drop the borrow
         // on `links` before accessing `other` to avoid a already borrowed error
         // from the `RefCell`.
-        drop(links);
+        let removed = this
+            .inner()
+            .links()
+            .is_some_and(|links| links.borrow_mut().remove(Link::forward(other.ptr), 1));
         // Remove a backward reference to `this` in `other`. This bookkeeping is
         // used for discovering cycles.
-        //
-        // SAFETY: `this` is a live `Rc` so the `links` on its inner allocation
-        // are an inhabited `MaybeUninit`.
-        let mut links = unsafe { other.inner().links().borrow_mut() };
-        links.remove(Link::backward(this.ptr), 1);
+        if let Some(links) = other.inner().links() {
+            links.borrow_mut().remove(Link::backward(this.ptr), 1);
+        }
+        removed
+    }
+
+    /// Perform bookkeeping to record that `this` has an owned reference to
+    /// `other` if the object graph stays within the limit set with
+    /// [`set_max_graph_size`].
+    ///
+    /// This behaves like [`Adopt::adopt_unchecked`], but returns an error
+    /// without recording the adoption if it would grow the object graph `this`
+    /// and `other` belong to beyond the limit. Adoptions that join two graphs
+    /// count the edges of both.
+    ///
+    /// If a limit is set, this function traverses the object graphs of `this`
+    /// and `other` and runs in `O(links + nodes)` time.
+    ///
+    /// This is an associated function that needs to be used as
+    /// `Rc::try_adopt(...)`. A method would interfere with methods of the same
+    /// name on the contents of a `Rc` used through `Deref`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GraphTooLarge`] if the object graph would record more than
+    /// [`max_graph_size`] adoptions.
+    ///
+    /// # Safety
+    ///
+    /// Callers must uphold the safety requirements of
+    /// [`Adopt::adopt_unchecked`] if this function returns `Ok(())`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cactusref::{set_max_graph_size, Adopt, GraphTooLarge, Rc};
+    ///
+    /// set_max_graph_size(2);
+    ///
+    /// let node = Rc::new(());
+    /// let clones = [Rc::clone(&node), Rc::clone(&node), Rc::clone(&node)];
+    /// unsafe {
+    ///     assert!(Rc::try_adopt(&node, &clones[0]).is_ok());
+    ///     assert!(Rc::try_adopt(&node, &clones[1]).is_ok());
+    ///     assert_eq!(
+    ///         Rc::try_adopt(&node, &clones[2]),
+    ///         Err(GraphTooLarge { limit: 2 })
+    ///     );
+    /// }
+    /// # Rc::unadopt(&node, &clones[0]);
+    /// # Rc::unadopt(&node, &clones[1]);
+    /// ```
+    ///
+    /// [`max_graph_size`]: crate::max_graph_size
+    /// [`set_max_graph_size`]: crate::set_max_graph_size
+    #[cfg(feature = "std")]
+    pub unsafe fn try_adopt(this: &Self, other: &Self) -> Result<(), GraphTooLarge> {
+        let limit = max_graph_size();
+        if limit != usize::MAX && !this.inner().is_dead() && !other.inner().is_dead() {
+            let nodes = graph_nodes(Link::forward(this.ptr));
+            let mut edges = graph_edges(&nodes);
+            if !nodes.contains(&Link::forward(other.ptr)) {
+                edges += graph_edges(&graph_nodes(Link::forward(other.ptr)));
+            }
+            if edges >= limit {
+                return Err(GraphTooLarge { limit });
+            }
+        }
+        // SAFETY: Callers must uphold the safety requirements of
+        // `adopt_unchecked`.
+        unsafe {
+            Rc::adopt_unchecked(this, other);
+        }
+        Ok(())
+    }
+
+    /// Perform bookkeeping to record that `this` has an owned reference to
+    /// each of `children`.
+    ///
+    /// This calls [`Adopt::adopt_unchecked`] once for each item, so a child
+    /// which appears multiple times is adopted multiple times. It centralizes
+    /// the bookkeeping loop of self-referential collections which have already
+    /// stored clones of their children.
+    ///
+    /// This is an associated function that needs to be used as
+    /// `Rc::extend_adopting(...)`. A method would interfere with methods of the
+    /// same name on the contents of a `Rc` used through `Deref`.
+    ///
+    /// # Safety
+    ///
+    /// Callers must ensure that `this` owns a distinct strong reference to each
+    /// item of `children`, as required by [`Adopt::adopt_unchecked`].
+    ///
+    /// # Examples
+    ///
+    /// The following implements a self-referential array.
+    ///
+    /// ```rust
+    /// use cactusref::Rc;
+    /// use std::cell::RefCell;
+    ///
+    /// #[derive(Default)]
+    /// struct Array {
+    ///     buffer: Vec<Rc<RefCell<Self>>>,
+    /// }
+    ///
+    /// let array = Rc::new(RefCell::new(Array::default()));
+    /// let items = (0..10).map(|_| Rc::clone(&array)).collect::<Vec<_>>();
+    /// array.borrow_mut().buffer.extend(items);
+    /// unsafe {
+    ///     Rc::extend_adopting(&array, &array.borrow().buffer);
+    /// }
+    /// let weak = Rc::downgrade(&array);
+    /// // 1 for the array binding, 10 for the `Rc`s in buffer
+    /// assert_eq!(Rc::strong_count(&array), 11);
+    /// drop(array);
+    /// assert!(weak.upgrade().is_none());
+    /// ```
+    pub unsafe fn extend_adopting<'a, I>(this: &Self, children: I)
+    where
+        I: IntoIterator<Item = &'a Rc<T>>,
+        T: 'a,
+    {
+        for child in children {
+            // SAFETY: Callers must ensure `this` owns a strong reference to
+            // each child.
+            unsafe {
+                Rc::adopt_unchecked(this, child);
+            }
+        }
+    }
+
+    /// Clone `other` and perform bookkeeping to record that `this` owns the
+    /// returned clone.
+    ///
+    /// This combines [`Rc::clone`] and [`Adopt::adopt_unchecked`] so the
+    /// strong reference the adoption claims is guaranteed to exist. Callers
+    /// only need to store the returned `Rc` in the `T` contained by `this`.
+    ///
+    /// No user code runs between incrementing the strong count of `other` and
+    /// recording the adoption, so a panic elsewhere in the program cannot
+    /// observe one without the other.
+    ///
+    /// This is an associated function that needs to be used as
+    /// `Rc::adopt_and_keep(...)`. A method would interfere with methods of the
+    /// same name on the contents of a `Rc` used through `Deref`.
+    ///
+    /// # Safety
+    ///
+    /// Callers must store the returned `Rc` in the `T` contained by `this`.
+    ///
+    /// Callers must call [`unadopt`] before the returned `Rc` is removed from
+    /// `this` and dropped.
+    ///
+    /// # Examples
+    ///
+    /// The following implements a self-referential array.
+    ///
+    /// ```rust
+    /// use cactusref::Rc;
+    /// use std::cell::RefCell;
+    ///
+    /// #[derive(Default)]
+    /// struct Array {
+    ///     buffer: Vec<Rc<RefCell<Self>>>,
+    /// }
+    ///
+    /// let array = Rc::new(RefCell::new(Array::default()));
+    /// for _ in 0..10 {
+    ///     let item = unsafe { Rc::adopt_and_keep(&array, &array) };
+    ///     array.borrow_mut().buffer.push(item);
+    /// }
+    /// let weak = Rc::downgrade(&array);
+    /// // 1 for the array binding, 10 for the `Rc`s in buffer
+    /// assert_eq!(Rc::strong_count(&array), 11);
+    /// drop(array);
+    /// assert!(weak.upgrade().is_none());
+    /// ```
+    ///
+    /// [`unadopt`]: Rc::unadopt
+    #[must_use = "the returned Rc must be stored in `this` to uphold the adoption"]
+    pub unsafe fn adopt_and_keep(this: &Self, other: &Self) -> Rc<T> {
+        let clone = Rc::clone(other);
+        // SAFETY: `clone` is an owned strong reference to `other` and callers
+        // must store it in `this`.
+        unsafe {
+            Rc::adopt_unchecked(this, &clone);
+        }
+        clone
+    }
+
+    /// Upgrade `child` and perform bookkeeping to record that `this` owns the
+    /// returned strong reference.
+    ///
+    /// This behaves like [`Rc::adopt_and_keep`] for `Rc`s reachable through a
+    /// [`Weak`] pointer. If the allocation `child` points to has been dropped,
+    /// no adoption is recorded and `None` is returned.
+    ///
+    /// This is an associated function that needs to be used as
+    /// `Rc::adopt_via_weak(...)`. A method would interfere with methods of the
+    /// same name on the contents of a `Rc` used through `Deref`.
+    ///
+    /// # Safety
+    ///
+    /// Callers must store the returned `Rc` in the `T` contained by `this`.
+    ///
+    /// Callers must call [`unadopt`] before the returned `Rc` is removed from
+    /// `this` and dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cactusref::Rc;
+    /// use std::cell::RefCell;
+    ///
+    /// struct Node {
+    ///     next: RefCell<Option<Rc<Node>>>,
+    /// }
+    ///
+    /// let node = Rc::new(Node { next: RefCell::new(None) });
+    /// let weak = Rc::downgrade(&node);
+    ///
+    /// let next = unsafe { Rc::adopt_via_weak(&node, &weak) };
+    /// *node.next.borrow_mut() = next;
+    /// assert_eq!(Rc::strong_count(&node), 2);
+    ///
+    /// drop(node);
+    /// assert!(weak.upgrade().is_none());
+    /// ```
+    ///
+    /// [`unadopt`]: Rc::unadopt
+    #[must_use = "the returned Rc must be stored in `this` to uphold the adoption"]
+    pub unsafe fn adopt_via_weak(this: &Self, child: &Weak<T>) -> Option<Rc<T>> {
+        let child = child.upgrade()?;
+        // SAFETY: `child` is an owned strong reference and callers must store
+        // it in `this`.
+        unsafe {
+            Rc::adopt_unchecked(this, &child);
+        }
+        Some(child)
+    }
+
+    /// Perform bookkeeping to record that `this` has removed all of its owned
+    /// references and that all owned references to `this` have been removed.
+    ///
+    /// This is equivalent to calling [`unadopt`] for every adoption `this` has
+    /// made and every adoption made of `this`, including self-adoptions. After
+    /// this call, `this` has no links in the object graph. Removing `this` may
+    /// split the object graph it belonged to into several graphs.
+    ///
+    /// This is useful for implementing `remove` or `pop` on self-referential
+    /// collections, where a node is unlinked from all of its neighbors before
+    /// it is returned to the caller.
+    ///
+    /// This is an associated function that needs to be used as
+    /// `Rc::detach_from_graph(...)`. A method would interfere with methods of
+    /// the same name on the contents of a `Rc` used through `Deref`.
+    ///
+    /// # Memory Leaks
+    ///
+    /// This function only updates bookkeeping. Strong references to `this`
+    /// which are still stored in other members of the object graph are
+    /// treated as external owners, which keeps the cycles they belong to
+    /// alive. Remove the `Rc`s from the graph before or after detaching to
+    /// avoid leaking them.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cactusref::Rc;
+    /// use std::cell::RefCell;
+    ///
+    /// struct Node {
+    ///     next: RefCell<Option<Rc<Node>>>,
+    /// }
+    ///
+    /// let first = Rc::new(Node { next: RefCell::new(None) });
+    /// let second = Rc::new(Node { next: RefCell::new(None) });
+    /// *first.next.borrow_mut() = Some(unsafe { Rc::adopt_and_keep(&first, &second) });
+    /// *second.next.borrow_mut() = Some(unsafe { Rc::adopt_and_keep(&second, &first) });
+    ///
+    /// // Unlink `second` from the ring.
+    /// let removed = first.next.borrow_mut().take();
+    /// let next = second.next.borrow_mut().take();
+    /// Rc::detach_from_graph(&second);
+    /// drop(removed);
+    /// drop(next);
+    ///
+    /// assert!(!Rc::is_graphed(&first));
+    /// assert!(!Rc::is_graphed(&second));
+    /// assert_eq!(Rc::strong_count(&second), 1);
+    /// ```
+    ///
+    /// [`unadopt`]: Rc::unadopt
+    pub fn detach_from_graph(this: &Self) {
+        // The links of dead `Rc`s have already been torn down.
+        if this.inner().is_dead() {
+            return;
+        }
+        let Some(links) = this.inner().links() else {
+            return;
+        };
+        let detached = links
+            .borrow_mut()
+            .extract_if(|_, _| true)
+            .collect::<Vec<_>>();
+        for (link, count) in detached {
+            let other = link.as_forward();
+            // Links to `this` itself were removed above.
+            if other.as_ptr() == this.ptr.as_ptr() {
+                continue;
+            }
+            let Some(other_links) = other.as_ref().links() else {
+                continue;
+            };
+            match link.kind() {
+                Kind::Forward => other_links
+                    .borrow_mut()
+                    .remove(Link::backward(this.ptr), count),
+                Kind::Backward => other_links
+                    .borrow_mut()
+                    .remove(Link::forward(this.ptr), count),
+                Kind::Loopback => continue,
+            };
+        }
+    }
+
+    /// Perform bookkeeping to record that `parent` has removed all of its
+    /// owned references to `child`, returning whether this split the object
+    /// graph they belonged to.
+    ///
+    /// This is equivalent to calling [`unadopt`] for every adoption of `child`
+    /// made by `parent`. If no other path of adoptions in either direction
+    /// connects `parent` and `child`, the subgraph containing `child` becomes
+    /// an independent object graph. Orphaned cycles in the detached subgraph
+    /// are collected when their last external owner is dropped, without
+    /// tracing the graph `parent` belongs to.
+    ///
+    /// Returns `false` if `parent` had not adopted `child` or if `parent` and
+    /// `child` are still in the same object graph.
+    ///
+    /// This is an associated function that needs to be used as
+    /// `Rc::try_detach_subgraph(...)`. A method would interfere with methods of
+    /// the same name on the contents of a `Rc` used through `Deref`.
+    ///
+    /// This function traverses the object graph of `parent` and runs in
+    /// `O(links + nodes)` time if `parent` and `child` are still graphed.
+    ///
+    /// # Memory Leaks
+    ///
+    /// This function only updates bookkeeping. Strong references to `child`
+    /// which are still stored in `parent` are treated as external owners,
+    /// which keeps the detached subgraph alive. Remove the `Rc`s from `parent`
+    /// before or after detaching to avoid leaking them.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cactusref::Rc;
+    /// use std::cell::RefCell;
+    ///
+    /// struct Node {
+    ///     children: RefCell<Vec<Rc<Node>>>,
+    /// }
+    ///
+    /// let root = Rc::new(Node { children: RefCell::new(vec![]) });
+    /// let branch = Rc::new(Node { children: RefCell::new(vec![]) });
+    /// let child = unsafe { Rc::adopt_and_keep(&root, &branch) };
+    /// root.children.borrow_mut().push(child);
+    ///
+    /// let removed = root.children.borrow_mut().pop();
+    /// assert!(Rc::try_detach_subgraph(&root, &branch));
+    /// drop(removed);
+    ///
+    /// assert!(!Rc::is_graphed(&root));
+    /// assert!(!Rc::is_graphed(&branch));
+    /// assert!(!Rc::try_detach_subgraph(&root, &branch));
+    /// ```
+    ///
+    /// [`unadopt`]: Rc::unadopt
+    #[allow(clippy::must_use_candidate)]
+    pub fn try_detach_subgraph(parent: &Self, child: &Self) -> bool {
+        // The links of dead `Rc`s have already been torn down.
+        if parent.inner().is_dead() || child.inner().is_dead() {
+            return false;
+        }
+        // `parent` and `child` may point to the same allocation. Drop the
+        // borrow on the links of `parent` before accessing `child` to avoid an
+        // already borrowed error from the `RefCell`.
+        let removed = parent.inner().links().is_some_and(|links| {
+            links
+                .borrow_mut()
+                .remove(Link::forward(child.ptr), usize::MAX)
+        });
+        if !removed {
+            return false;
+        }
+        if let Some(links) = child.inner().links() {
+            links
+                .borrow_mut()
+                .remove(Link::backward(parent.ptr), usize::MAX);
+        }
+        !Rc::same_graph(parent, child)
     }
 }
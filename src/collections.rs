@@ -0,0 +1,12 @@
+//! Collections built on cycle-aware [`Rc`](crate::Rc)s.
+//!
+//! The collections in this module use [`Adopt`](crate::Adopt) to own strong
+//! references to their own nodes. They encapsulate the adoption bookkeeping,
+//! so they are safe to use and their nodes are deallocated by `Rc`'s cycle
+//! detection once the collection is dropped.
+
+mod graph;
+mod linked_list;
+
+pub use graph::{Graph, Neighbors, NodeId};
+pub use linked_list::{Iter, LinkedList};
@@ -0,0 +1,304 @@
+use alloc::vec::Vec;
+use core::cell::{Ref, RefCell};
+use core::fmt;
+use core::iter::FusedIterator;
+
+use crate::{Adopt, Rc};
+
+struct Node<T> {
+    id: NodeId,
+    edges: RefCell<Vec<Rc<Node<T>>>>,
+    value: T,
+}
+
+/// Identifies a node in a [`Graph`].
+///
+/// `NodeId`s are returned by [`Graph::add_node`] and are only meaningful for
+/// the graph which returned them. Ids of removed nodes are not reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(usize);
+
+impl NodeId {
+    /// Returns the index of this node in the order it was added to its graph.
+    #[must_use]
+    pub const fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// A directed graph with nodes stored as adjacency lists.
+///
+/// Each node owns strong references to the nodes its outgoing edges point to.
+/// The graph records these references with [`Adopt`], so nodes which form
+/// cycles are deallocated by `Rc`'s cycle detection once the graph is dropped.
+///
+/// Nodes are addressed by the [`NodeId`] returned when they are added. At most
+/// one edge is stored for each ordered pair of nodes. Edges from a node to
+/// itself are allowed.
+///
+/// # Examples
+///
+/// ```
+/// use cactusref::collections::Graph;
+///
+/// let mut graph = Graph::new();
+/// let a = graph.add_node("a");
+/// let b = graph.add_node("b");
+/// let c = graph.add_node("c");
+/// graph.add_edge(a, b);
+/// graph.add_edge(b, c);
+/// graph.add_edge(c, a);
+///
+/// assert_eq!(graph.neighbors(a).collect::<Vec<_>>(), [b]);
+/// assert_eq!(graph.edge_count(), 3);
+///
+/// // The cycle `a -> b -> c -> a` is deallocated with the graph.
+/// drop(graph);
+/// ```
+pub struct Graph<T> {
+    nodes: Vec<Option<Rc<Node<T>>>>,
+    node_count: usize,
+}
+
+impl<T> Default for Graph<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Graph<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(
+                self.nodes
+                    .iter()
+                    .flatten()
+                    .map(|node| (node.id, &node.value)),
+            )
+            .finish()
+    }
+}
+
+impl<T> Graph<T> {
+    /// Creates an empty `Graph`.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            node_count: 0,
+        }
+    }
+
+    /// Returns the number of nodes in the graph.
+    #[must_use]
+    pub const fn node_count(&self) -> usize {
+        self.node_count
+    }
+
+    /// Returns the number of edges in the graph.
+    ///
+    /// This function takes `O(nodes)` time.
+    #[must_use]
+    pub fn edge_count(&self) -> usize {
+        self.nodes
+            .iter()
+            .flatten()
+            .map(|node| node.edges.borrow().len())
+            .sum()
+    }
+
+    /// Returns `true` if the graph contains no nodes.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.node_count == 0
+    }
+
+    fn node(&self, id: NodeId) -> Option<&Rc<Node<T>>> {
+        self.nodes.get(id.0)?.as_ref()
+    }
+
+    fn expect_node(&self, id: NodeId) -> &Rc<Node<T>> {
+        match self.node(id) {
+            Some(node) => node,
+            None => panic!("{id:?} is not in the graph"),
+        }
+    }
+
+    /// Adds a node with the given value to the graph and returns its id.
+    pub fn add_node(&mut self, value: T) -> NodeId {
+        let id = NodeId(self.nodes.len());
+        self.nodes.push(Some(Rc::new(Node {
+            id,
+            edges: RefCell::new(Vec::new()),
+            value,
+        })));
+        self.node_count += 1;
+        id
+    }
+
+    /// Returns a reference to the value of the node with the given id, or
+    /// `None` if it is not in the graph.
+    #[must_use]
+    pub fn get(&self, id: NodeId) -> Option<&T> {
+        self.node(id).map(|node| &node.value)
+    }
+
+    /// Removes the node with the given id and all of its incoming and outgoing
+    /// edges from the graph and returns its value.
+    ///
+    /// Returns `None` if the node is not in the graph.
+    ///
+    /// This function takes `O(nodes + edges)` time to find incoming edges.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::collections::Graph;
+    ///
+    /// let mut graph = Graph::new();
+    /// let a = graph.add_node("a");
+    /// let b = graph.add_node("b");
+    /// graph.add_edge(a, b);
+    /// graph.add_edge(b, a);
+    ///
+    /// assert_eq!(graph.remove_node(a), Some("a"));
+    /// assert_eq!(graph.remove_node(a), None);
+    /// assert_eq!(graph.edge_count(), 0);
+    /// ```
+    pub fn remove_node(&mut self, id: NodeId) -> Option<T> {
+        let node = self.nodes.get_mut(id.0)?.take()?;
+        self.node_count -= 1;
+        for source in self.nodes.iter().flatten() {
+            remove_edge(source, &node);
+        }
+        let edges = node.edges.take();
+        for target in &edges {
+            Rc::unadopt(&node, target);
+        }
+        drop(edges);
+
+        // All edges referencing `node` have been released.
+        match Rc::try_unwrap(node) {
+            Ok(node) => Some(node.value),
+            Err(_) => unreachable!("Graph node is uniquely owned after removing its edges"),
+        }
+    }
+
+    /// Adds an edge from the node `from` to the node `to`.
+    ///
+    /// Returns `false` if the edge was already in the graph.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either node is not in the graph.
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId) -> bool {
+        let target = self.expect_node(to);
+        let source = self.expect_node(from);
+        if contains_edge(source, target) {
+            return false;
+        }
+        // SAFETY: The adopted clone is stored in the edges of `source` and is
+        // unadopted when it is removed.
+        let edge = unsafe { Rc::adopt_and_keep(source, target) };
+        source.edges.borrow_mut().push(edge);
+        true
+    }
+
+    /// Removes the edge from the node `from` to the node `to`.
+    ///
+    /// Returns `false` if the edge was not in the graph.
+    ///
+    /// Removing an edge may disconnect a cycle of nodes from the rest of the
+    /// graph. Nodes stay in the graph until they are removed with
+    /// [`remove_node`](Self::remove_node) or the graph is dropped.
+    pub fn remove_edge(&mut self, from: NodeId, to: NodeId) -> bool {
+        match (self.node(from), self.node(to)) {
+            (Some(source), Some(target)) => remove_edge(source, target),
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the graph contains an edge from the node `from` to the
+    /// node `to`.
+    #[must_use]
+    pub fn contains_edge(&self, from: NodeId, to: NodeId) -> bool {
+        match (self.node(from), self.node(to)) {
+            (Some(source), Some(target)) => contains_edge(source, target),
+            _ => false,
+        }
+    }
+
+    /// Returns an iterator over the ids of the nodes the outgoing edges of the
+    /// node `id` point to, in the order the edges were added.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the node is not in the graph.
+    #[must_use]
+    pub fn neighbors(&self, id: NodeId) -> Neighbors<'_, T> {
+        Neighbors {
+            edges: self.expect_node(id).edges.borrow(),
+            pos: 0,
+        }
+    }
+
+    /// Returns an iterator over the ids of the nodes in the graph, in the order
+    /// they were added.
+    pub fn node_ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.nodes.iter().flatten().map(|node| node.id)
+    }
+}
+
+fn contains_edge<T>(source: &Rc<Node<T>>, target: &Rc<Node<T>>) -> bool {
+    source
+        .edges
+        .borrow()
+        .iter()
+        .any(|edge| Rc::ptr_eq(edge, target))
+}
+
+fn remove_edge<T>(source: &Rc<Node<T>>, target: &Rc<Node<T>>) -> bool {
+    let mut edges = source.edges.borrow_mut();
+    let Some(pos) = edges.iter().position(|edge| Rc::ptr_eq(edge, target)) else {
+        return false;
+    };
+    let edge = edges.remove(pos);
+    drop(edges);
+    Rc::unadopt(source, &edge);
+    true
+}
+
+/// An iterator over the neighbors of a node in a [`Graph`].
+///
+/// This struct is created by [`Graph::neighbors`].
+pub struct Neighbors<'a, T> {
+    edges: Ref<'a, Vec<Rc<Node<T>>>>,
+    pos: usize,
+}
+
+impl<T> fmt::Debug for Neighbors<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.edges[self.pos..].iter().map(|edge| edge.id))
+            .finish()
+    }
+}
+
+impl<T> Iterator for Neighbors<'_, T> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let edge = self.edges.get(self.pos)?;
+        self.pos += 1;
+        Some(edge.id)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.edges.len() - self.pos;
+        (len, Some(len))
+    }
+}
+
+impl<T> ExactSizeIterator for Neighbors<'_, T> {}
+
+impl<T> FusedIterator for Neighbors<'_, T> {}
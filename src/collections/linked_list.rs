@@ -0,0 +1,253 @@
+use core::cell::RefCell;
+use core::fmt;
+use core::iter::FusedIterator;
+
+use crate::Rc;
+
+struct Node<T> {
+    prev: RefCell<Option<Rc<Node<T>>>>,
+    next: RefCell<Option<Rc<Node<T>>>>,
+    value: T,
+}
+
+/// A doubly-linked list with owned nodes.
+///
+/// Each node owns strong references to its neighbors, so the nodes of a list
+/// form a cycle of `Rc`s. The list records these references with [`Adopt`]
+/// and dropping the list deallocates all of its nodes with `Rc`'s cycle
+/// detection.
+///
+/// [`Adopt`]: crate::Adopt
+///
+/// # Examples
+///
+/// ```
+/// use cactusref::collections::LinkedList;
+///
+/// let mut list = LinkedList::new();
+/// list.push_back(1);
+/// list.push_back(2);
+/// list.push_back(3);
+/// assert_eq!(list.iter().copied().collect::<Vec<_>>(), [1, 2, 3]);
+///
+/// assert_eq!(list.pop_front(), Some(1));
+/// assert_eq!(list.len(), 2);
+/// ```
+pub struct LinkedList<T> {
+    head: Option<Rc<Node<T>>>,
+    tail: Option<Rc<Node<T>>>,
+    len: usize,
+}
+
+impl<T> Default for LinkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for LinkedList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> LinkedList<T> {
+    /// Creates an empty `LinkedList`.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            head: None,
+            tail: None,
+            len: 0,
+        }
+    }
+
+    /// Returns the number of elements in the list.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the list contains no elements.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Appends an element to the back of the list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::collections::LinkedList;
+    ///
+    /// let mut list = LinkedList::new();
+    /// list.push_back("a");
+    /// list.push_back("b");
+    /// assert_eq!(list.len(), 2);
+    /// ```
+    pub fn push_back(&mut self, value: T) {
+        let node = Rc::new(Node {
+            prev: RefCell::new(None),
+            next: RefCell::new(None),
+            value,
+        });
+        if let Some(tail) = self.tail.take() {
+            // SAFETY: The adopted clones are stored in the `next` and `prev`
+            // fields of the adopting nodes.
+            unsafe {
+                *tail.next.borrow_mut() = Some(Rc::adopt_and_keep(&tail, &node));
+                *node.prev.borrow_mut() = Some(Rc::adopt_and_keep(&node, &tail));
+            }
+        } else {
+            self.head = Some(Rc::clone(&node));
+        }
+        self.tail = Some(node);
+        self.len += 1;
+    }
+
+    /// Removes the first element and returns it, or `None` if the list is
+    /// empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::collections::LinkedList;
+    ///
+    /// let mut list = LinkedList::new();
+    /// assert_eq!(list.pop_front(), None);
+    ///
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// assert_eq!(list.pop_front(), Some(1));
+    /// assert_eq!(list.pop_front(), Some(2));
+    /// assert_eq!(list.pop_front(), None);
+    /// ```
+    pub fn pop_front(&mut self) -> Option<T> {
+        let head = self.head.take()?;
+        let next = head.next.take();
+        let prev = next.as_ref().and_then(|next| next.prev.take());
+        Rc::detach_from_graph(&head);
+        drop(prev);
+        if let Some(next) = next {
+            self.head = Some(next);
+        } else {
+            // `head` was the only node.
+            self.tail = None;
+        }
+        self.len -= 1;
+
+        // All neighbors have released their references to `head`.
+        match Rc::try_unwrap(head) {
+            Ok(node) => Some(node.value),
+            Err(_) => unreachable!("LinkedList node is uniquely owned after unlinking"),
+        }
+    }
+
+    /// Returns a reference to the first element, or `None` if the list is
+    /// empty.
+    #[must_use]
+    pub fn front(&self) -> Option<&T> {
+        self.head.as_ref().map(|head| &head.value)
+    }
+
+    /// Returns a reference to the last element, or `None` if the list is
+    /// empty.
+    #[must_use]
+    pub fn back(&self) -> Option<&T> {
+        self.tail.as_ref().map(|tail| &tail.value)
+    }
+
+    /// Returns an iterator over the elements of the list, from front to back.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::collections::LinkedList;
+    ///
+    /// let list = ["a", "b", "c"].into_iter().collect::<LinkedList<_>>();
+    /// let mut iter = list.iter();
+    /// assert_eq!(iter.next(), Some(&"a"));
+    /// assert_eq!(iter.next(), Some(&"b"));
+    /// assert_eq!(iter.next(), Some(&"c"));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    #[must_use]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            next: self.head.as_deref(),
+            len: self.len,
+        }
+    }
+}
+
+impl<T> Extend<T> for LinkedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push_back(value);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for LinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<'a, T> IntoIterator for &'a LinkedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the elements of a [`LinkedList`].
+///
+/// This struct is created by [`LinkedList::iter`].
+pub struct Iter<'a, T> {
+    next: Option<&'a Node<T>>,
+    len: usize,
+}
+
+impl<T: fmt::Debug> fmt::Debug for Iter<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Iter").field(&self.len).finish()
+    }
+}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            next: self.next,
+            len: self.len,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.next?;
+        let next = node.next.borrow().as_ref().map(Rc::as_ptr);
+        // SAFETY: The list is borrowed for `'a` and nodes are only unlinked
+        // through `&mut LinkedList`, so every node reachable from the head
+        // outlives `'a`.
+        self.next = next.map(|next| unsafe { &*next });
+        self.len -= 1;
+        Some(&node.value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
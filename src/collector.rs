@@ -0,0 +1,245 @@
+//! Deferred collection of orphaned cycles.
+//!
+//! By default, dropping an [`Rc`] which belongs to an object graph traces the
+//! graph and deallocates it synchronously if it is an orphaned cycle. For
+//! large graphs, this trace runs in `O(links + nodes)` time and may cause
+//! latency spikes.
+//!
+//! Deferred mode moves the trace and the deallocation to a safe point chosen
+//! by the caller. While deferred mode is enabled on a thread, [`Rc`]'s `Drop`
+//! implementation does not trace the object graph. Instead, the dropped
+//! allocation is enqueued on a thread-local work list as a candidate. The
+//! work list holds a weak reference to each candidate. [`collect`] drains the
+//! work list, traces the object graph of each candidate which is still alive,
+//! and frees the graphs which are orphaned cycles.
+//!
+//! Orphaned cycles are kept alive by the strong references their members
+//! hold to each other until they are collected, so [`Weak`] pointers to them
+//! may still be upgraded. Upgrading a `Weak` to a member of an orphaned cycle
+//! makes it reachable again and the cycle will not be deallocated when it is
+//! collected.
+//!
+//! Candidates which are still pending when the thread exits are collected
+//! when the thread-local work list is destroyed.
+//!
+//! [`Weak`]: crate::Weak
+//!
+//! # Examples
+//!
+//! ```
+//! use std::cell::RefCell;
+//!
+//! use cactusref::{collector, Adopt, Rc};
+//!
+//! struct Node {
+//!     next: RefCell<Option<Rc<Node>>>,
+//! }
+//!
+//! let first = Rc::new(Node { next: RefCell::new(None) });
+//! let second = Rc::new(Node { next: RefCell::new(Some(Rc::clone(&first))) });
+//! *first.next.borrow_mut() = Some(Rc::clone(&second));
+//! unsafe {
+//!     Rc::adopt_unchecked(&first, &second);
+//!     Rc::adopt_unchecked(&second, &first);
+//!     // SAFETY: The values in the cycle do not borrow any data.
+//!     collector::defer();
+//! }
+//! let weak = Rc::downgrade(&first);
+//! drop(first);
+//! drop(second);
+//!
+//! // The cycle is orphaned, but not yet deallocated.
+//! assert_eq!(collector::pending(), 2);
+//! assert_eq!(weak.strong_count(), 1);
+//!
+//! assert_eq!(collector::collect(), 2);
+//! assert!(weak.upgrade().is_none());
+//!
+//! collector::immediate();
+//! ```
+
+use core::cell::{Cell, RefCell};
+use core::mem::ManuallyDrop;
+use core::ptr::NonNull;
+use std::thread_local;
+use std::vec::Vec;
+
+use crate::drop::collect_orphaned_cycle;
+use crate::hash::HashSet;
+use crate::{Rc, Weak};
+
+thread_local! {
+    static DEFERRED: Cell<bool> = const { Cell::new(false) };
+    static PENDING: RefCell<WorkList> = RefCell::new(WorkList::default());
+}
+
+#[derive(Default)]
+struct WorkList {
+    candidates: Vec<Pending>,
+    // The allocations in `candidates`, so each allocation is enqueued at most
+    // once.
+    enqueued: HashSet<NonNull<()>>,
+}
+
+/// A type-erased weak reference to a dropped `Rc` which may be a member of an
+/// orphaned cycle.
+///
+/// Dropping a `Pending` traces the object graph of the candidate if it is
+/// still alive, deallocates the graph if it is an orphaned cycle, and drops
+/// the weak reference.
+struct Pending {
+    ptr: NonNull<()>,
+    release: unsafe fn(NonNull<()>),
+}
+
+impl Drop for Pending {
+    fn drop(&mut self) {
+        unsafe {
+            (self.release)(self.ptr);
+        }
+    }
+}
+
+unsafe fn release<T>(ptr: NonNull<()>) {
+    let ptr = ptr.as_ptr().cast::<T>();
+    let weak = Weak::from_raw(ptr);
+    if weak.strong_count() > 0 {
+        // Borrow the candidate without taking a strong reference so the trace
+        // observes the same strong counts as `Rc`'s `Drop` implementation.
+        let this = ManuallyDrop::new(Rc::from_raw(ptr));
+        collect_orphaned_cycle(&this);
+    }
+    drop(weak);
+}
+
+/// Enable deferred collection of orphaned cycles on the current thread.
+///
+/// Until [`immediate`] is called, orphaned cycles are enqueued instead of
+/// being deallocated when their last external owner is dropped. Call
+/// [`collect`] to deallocate them.
+///
+/// # Safety
+///
+/// `Rc`'s `Drop` implementation permits its value to borrow data which does
+/// not outlive the `Rc`. Values in deferred cycles are dropped when
+/// [`collect`] is called or when the thread exits, which may be after the
+/// `Rc` was dropped.
+///
+/// Callers must ensure that all data borrowed by values in cycles which are
+/// orphaned while deferred mode is enabled outlives the next call to
+/// [`collect`], or the thread if `collect` is never called.
+pub unsafe fn defer() {
+    DEFERRED.with(|deferred| deferred.set(true));
+}
+
+/// Disable deferred collection of orphaned cycles on the current thread.
+///
+/// Orphaned cycles are deallocated as soon as their last external owner is
+/// dropped. Candidates which were enqueued while deferred mode was enabled
+/// remain pending until [`collect`] is called.
+pub fn immediate() {
+    DEFERRED.with(|deferred| deferred.set(false));
+}
+
+/// Returns whether deferred collection of orphaned cycles is enabled on the
+/// current thread.
+#[must_use]
+pub fn is_deferred() -> bool {
+    DEFERRED.with(Cell::get)
+}
+
+/// Returns the number of candidates pending collection on the current thread.
+///
+/// Each dropped `Rc` which belongs to an object graph is a candidate. An
+/// allocation is enqueued at most once, so an orphaned cycle has up to one
+/// candidate per member.
+#[must_use]
+pub fn pending() -> usize {
+    PENDING.with(|pending| pending.borrow().candidates.len())
+}
+
+/// Deallocate all orphaned cycles among the candidates pending collection on
+/// the current thread.
+///
+/// The object graph of each candidate which is still alive is traced and
+/// deallocated if it is an orphaned cycle. Candidates in a cycle which was
+/// deallocated through an earlier candidate are released without a trace.
+/// Cycles which become orphaned while the work list is drained, for example
+/// because a value in a collected cycle owned the last external reference to
+/// another cycle, are deallocated immediately.
+///
+/// Returns the number of work list entries which were drained. An entry whose
+/// graph is reachable is released without deallocating the graph.
+#[allow(clippy::must_use_candidate)]
+pub fn collect() -> usize {
+    // Disable deferred mode while the work list is drained so `Rc`s dropped
+    // while deallocating a cycle are collected instead of being enqueued.
+    let _guard = Immediate::new();
+    let mut collected = 0;
+    loop {
+        // Pop the entry in its own statement so the work list is not borrowed
+        // while the cycle is deallocated.
+        let next = PENDING.with(|pending| {
+            let mut pending = pending.borrow_mut();
+            let next = pending.candidates.pop()?;
+            pending.enqueued.remove(&next.ptr);
+            Some(next)
+        });
+        let Some(next) = next else {
+            return collected;
+        };
+        drop(next);
+        collected += 1;
+    }
+}
+
+/// Disables deferred mode until dropped, restoring the previous mode even if
+/// a `Drop` implementation panics during collection.
+struct Immediate {
+    deferred: bool,
+}
+
+impl Immediate {
+    fn new() -> Self {
+        Self {
+            deferred: DEFERRED.with(|deferred| deferred.replace(false)),
+        }
+    }
+}
+
+impl Drop for Immediate {
+    fn drop(&mut self) {
+        DEFERRED.with(|deferred| deferred.set(self.deferred));
+    }
+}
+
+/// Enqueue `this` as a candidate for cycle collection if deferred mode is
+/// enabled on the current thread.
+///
+/// `this` must have been released by `Rc`'s `Drop` implementation and must
+/// still be alive. If deferred mode is enabled, the work list owns a weak
+/// reference to `this` and this function returns `true` without tracing the
+/// object graph.
+pub(crate) fn enqueue<T>(this: &Rc<T>) -> bool {
+    if !DEFERRED.with(Cell::get) {
+        return false;
+    }
+    // If the work list has been destroyed because the thread is exiting,
+    // collect the cycle immediately.
+    PENDING
+        .try_with(|pending| {
+            let mut pending = pending.borrow_mut();
+            let ptr = Rc::as_ptr(this).cast_mut().cast::<()>();
+            // SAFETY: `Rc::as_ptr` never returns null.
+            let ptr = unsafe { NonNull::new_unchecked(ptr) };
+            if pending.enqueued.insert(ptr) {
+                let weak = Weak::into_raw(Rc::downgrade(this));
+                debug_assert_eq!(weak.cast::<()>(), ptr.as_ptr().cast_const());
+                pending.candidates.push(Pending {
+                    ptr,
+                    release: release::<T>,
+                });
+            }
+        })
+        .is_ok()
+}
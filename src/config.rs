@@ -0,0 +1,211 @@
+use core::cell::Cell;
+use std::thread_local;
+
+thread_local! {
+    static MAX_GRAPH_SIZE: Cell<usize> = const { Cell::new(usize::MAX) };
+    static DROP_ORDER: Cell<DropOrder> = const { Cell::new(DropOrder::Unspecified) };
+    static TRACE_BUDGET: Cell<usize> = const { Cell::new(usize::MAX) };
+    static ADOPTION_SEQUENCE: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Limit the number of adoptions an object graph may record on the current
+/// thread.
+///
+/// [`Rc::try_adopt`] refuses to record an adoption which would grow the object
+/// graph `this` and `other` belong to beyond `n` edges. This is a safety valve
+/// for code which builds graphs with shapes controlled by untrusted input.
+///
+/// The limit defaults to [`usize::MAX`], which disables the check. The limit
+/// is not enforced by [`Adopt::adopt_unchecked`].
+///
+/// [`Adopt::adopt_unchecked`]: crate::Adopt::adopt_unchecked
+/// [`Rc::try_adopt`]: crate::Rc::try_adopt
+///
+/// # Examples
+///
+/// ```
+/// use cactusref::{set_max_graph_size, Adopt, GraphTooLarge, Rc};
+///
+/// set_max_graph_size(1);
+///
+/// let first = Rc::new(());
+/// let second = Rc::new(());
+/// let first_clone = Rc::clone(&first);
+/// let second_clone = Rc::clone(&second);
+/// unsafe {
+///     assert_eq!(Rc::try_adopt(&first, &second_clone), Ok(()));
+///     assert_eq!(
+///         Rc::try_adopt(&second, &first_clone),
+///         Err(GraphTooLarge { limit: 1 })
+///     );
+/// }
+/// # Rc::unadopt(&first, &second_clone);
+/// ```
+pub fn set_max_graph_size(n: usize) {
+    MAX_GRAPH_SIZE.with(|max| max.set(n));
+}
+
+/// Returns the maximum number of adoptions an object graph may record on the
+/// current thread.
+///
+/// See [`set_max_graph_size`].
+#[must_use]
+pub fn max_graph_size() -> usize {
+    MAX_GRAPH_SIZE.with(Cell::get)
+}
+
+/// Limit the number of nodes `Rc`'s [`Drop`] implementation may visit on the
+/// current thread when determining whether an object graph is orphaned.
+///
+/// Dropping an adopted `Rc` traverses the object graph it belongs to, which
+/// takes time proportional to the size of the graph. If the traversal would
+/// visit more than `max_nodes` nodes, it is abandoned, the graph is
+/// conservatively treated as reachable, and a warning is logged. This bounds
+/// the pause time of each drop at the cost of leaking orphaned graphs which
+/// are larger than the budget.
+///
+/// A leaked graph is traversed again if another of its members is dropped,
+/// so raising the budget before dropping the last external owner allows it to
+/// be collected.
+///
+/// The budget defaults to [`usize::MAX`], which disables the check.
+///
+/// # Examples
+///
+/// ```
+/// use std::cell::RefCell;
+///
+/// use cactusref::{set_trace_budget, Adopt, Rc};
+///
+/// struct Node {
+///     next: RefCell<Option<Rc<Node>>>,
+/// }
+///
+/// let first = Rc::new(Node { next: RefCell::new(None) });
+/// let second = Rc::new(Node { next: RefCell::new(Some(Rc::clone(&first))) });
+/// *first.next.borrow_mut() = Some(Rc::clone(&second));
+/// unsafe {
+///     Rc::adopt_unchecked(&first, &second);
+///     Rc::adopt_unchecked(&second, &first);
+/// }
+/// let weak = Rc::downgrade(&first);
+///
+/// set_trace_budget(1);
+/// drop(first);
+/// drop(second);
+/// // The cycle has two nodes, so it is leaked.
+/// let leaked = weak.upgrade().unwrap();
+///
+/// set_trace_budget(usize::MAX);
+/// drop(leaked);
+/// assert!(weak.upgrade().is_none());
+/// ```
+pub fn set_trace_budget(max_nodes: usize) {
+    TRACE_BUDGET.with(|budget| budget.set(max_nodes));
+}
+
+/// Returns the maximum number of nodes `Rc`'s [`Drop`] implementation may
+/// visit on the current thread when determining whether an object graph is
+/// orphaned.
+///
+/// See [`set_trace_budget`].
+#[must_use]
+pub fn trace_budget() -> usize {
+    TRACE_BUDGET.with(Cell::get)
+}
+
+/// The order in which the values of an orphaned cycle are dropped.
+///
+/// See [`set_drop_order`].
+#[non_exhaustive]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DropOrder {
+    /// Values are dropped in an unspecified order which may differ between
+    /// runs.
+    ///
+    /// This is the default and avoids the cost of sorting cycle members.
+    #[default]
+    Unspecified,
+    /// Values are dropped in ascending order of the address of their
+    /// allocation.
+    ///
+    /// For a given sequence of allocations, this order is reproducible, which
+    /// makes side effects of `T::drop` like logging deterministic. Sorting the
+    /// members of a cycle takes `O(nodes * log(nodes))` time.
+    Address,
+    /// Values are dropped in the reverse of the order in which their `Rc`s
+    /// first adopted an `Rc` they still own on the current thread.
+    ///
+    /// For structures built by adopting each new node from the previous one,
+    /// like a linked list, this drops the tail before the head. Each adoption
+    /// link records a sequence number when it is created, which is forgotten
+    /// when the link is removed by [`Adopt::unadopt`]. Only adoptions made
+    /// while this order is set are sequenced. Members without a sequenced
+    /// adoption are dropped last, in address order. Sorting the members of a
+    /// cycle takes `O(nodes * log(nodes))` time.
+    ///
+    /// [`Adopt::unadopt`]: crate::Adopt::unadopt
+    ReverseAdoption,
+}
+
+/// Set the order in which the values of orphaned cycles are dropped on the
+/// current thread.
+///
+/// Orphaned cycles are collected in an unspecified order by default. Tests
+/// which assert on side effects of `T::drop` can opt in to a deterministic
+/// order.
+///
+/// # Examples
+///
+/// ```
+/// use std::cell::RefCell;
+///
+/// use cactusref::{set_drop_order, Adopt, DropOrder, Rc};
+///
+/// struct Node {
+///     next: RefCell<Option<Rc<Node>>>,
+/// }
+///
+/// set_drop_order(DropOrder::Address);
+///
+/// let first = Rc::new(Node { next: RefCell::new(None) });
+/// let second = Rc::new(Node { next: RefCell::new(Some(Rc::clone(&first))) });
+/// *first.next.borrow_mut() = Some(Rc::clone(&second));
+/// unsafe {
+///     Rc::adopt_unchecked(&first, &second);
+///     Rc::adopt_unchecked(&second, &first);
+/// }
+/// // The values of `first` and `second` are dropped in address order.
+/// drop(first);
+/// drop(second);
+/// ```
+pub fn set_drop_order(order: DropOrder) {
+    DROP_ORDER.with(|drop_order| drop_order.set(order));
+}
+
+/// Returns the order in which the values of orphaned cycles are dropped on the
+/// current thread.
+///
+/// See [`set_drop_order`].
+#[must_use]
+pub fn drop_order() -> DropOrder {
+    DROP_ORDER.with(Cell::get)
+}
+
+/// Returns the next sequence number for an adoption on the current thread, or
+/// 0 if adoptions are not sequenced.
+///
+/// Sequence numbers start at 1 and increase monotonically. They order members
+/// of a cycle for [`DropOrder::ReverseAdoption`], so the counter is only
+/// advanced while that order is set. Other orders pay for one thread local
+/// read when a link is first recorded.
+pub(crate) fn next_adoption_sequence() -> u64 {
+    if drop_order() != DropOrder::ReverseAdoption {
+        return 0;
+    }
+    ADOPTION_SEQUENCE.with(|sequence| {
+        let next = sequence.get() + 1;
+        sequence.set(next);
+        next
+    })
+}
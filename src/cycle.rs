@@ -1,11 +1,776 @@
 use alloc::vec;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::mem::size_of;
+#[cfg(debug_assertions)]
+use core::ptr;
 
+#[cfg(feature = "std")]
+use crate::config::trace_budget;
+#[cfg(debug_assertions)]
+use crate::error::GraphInvariantViolation;
 use crate::hash::{HashMap, HashSet};
-use crate::link::{Kind, Link};
-use crate::rc::RcInnerPtr;
+use crate::link::{Kind, Link, Links};
+use crate::rc::{RcBox, RcInnerPtr};
 use crate::Rc;
 
 impl<T> Rc<T> {
+    /// Gets the number of strong (`Rc`) pointers to this allocation that are
+    /// not owned by other members of a cycle this `Rc` belongs to.
+    ///
+    /// [`Rc::strong_count`] includes references held by adopting `Rc`s in the
+    /// object graph. This function subtracts the adoptions recorded by members
+    /// of cycles reachable from `this`, which yields the number of "external"
+    /// owners of the allocation. This is the same quantity `Rc`'s [`Drop`]
+    /// implementation uses to determine whether a cycle is orphaned.
+    ///
+    /// `this` is an external owner, so the returned count is at least 1.
+    ///
+    /// This function traverses the object graph and runs in `O(links + nodes)`
+    /// time if `this` has any adoptions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::{Adopt, Rc};
+    ///
+    /// let node = Rc::new(());
+    /// let clone = Rc::clone(&node);
+    /// unsafe {
+    ///     Rc::adopt_unchecked(&node, &clone);
+    /// }
+    ///
+    /// assert_eq!(Rc::strong_count(&node), 2);
+    /// assert_eq!(Rc::strong_count_excluding_cycle(&node), 1);
+    /// # Rc::unadopt(&node, &clone);
+    /// ```
+    #[must_use]
+    pub fn strong_count_excluding_cycle(this: &Self) -> usize {
+        let strong = Rc::strong_count(this);
+        if !this.inner().has_links() {
+            return strong;
+        }
+        let cycle = cycle_refs(Link::forward(this.ptr));
+        let cycle_owned_refs = cycle
+            .get(&Link::forward(this.ptr))
+            .copied()
+            .unwrap_or_default();
+        strong.saturating_sub(cycle_owned_refs)
+    }
+
+    /// Returns whether `this` is graphed and has external owners other than
+    /// `this`.
+    ///
+    /// External owners are strong references which are not held by members of
+    /// cycles reachable from `this`, as counted by
+    /// [`Rc::strong_count_excluding_cycle`]. Such an `Rc` is a likely entry
+    /// point into its cycle, which stays reachable through it after `this` is
+    /// dropped. This is the inverse of the check `Rc`'s [`Drop`]
+    /// implementation uses to find orphaned cycles, and can be used to find
+    /// candidate roots for manual analysis of an object graph.
+    ///
+    /// Returns `false` if `this` is not graphed.
+    ///
+    /// This function traverses the object graph and runs in `O(links + nodes)`
+    /// time if `this` has any adoptions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::{Adopt, Rc};
+    ///
+    /// let node = Rc::new(());
+    /// assert!(!Rc::is_cycle_root(&node));
+    ///
+    /// let clone = Rc::clone(&node);
+    /// unsafe {
+    ///     Rc::adopt_unchecked(&node, &clone);
+    /// }
+    /// assert!(!Rc::is_cycle_root(&node));
+    ///
+    /// let external = Rc::clone(&node);
+    /// assert!(Rc::is_cycle_root(&node));
+    /// # drop(external);
+    /// # Rc::unadopt(&node, &clone);
+    /// ```
+    #[must_use]
+    pub fn is_cycle_root(this: &Self) -> bool {
+        if !this.inner().has_links() {
+            return false;
+        }
+        // Exclude the strong reference held by `this`.
+        Rc::strong_count_excluding_cycle(this) > 1
+    }
+
+    /// Returns the number of nodes in the cycle `this` belongs to if `this` is
+    /// the only external owner of the cycle.
+    ///
+    /// This is the number of allocations `Rc`'s [`Drop`] implementation would
+    /// deallocate if `this` were dropped. `this` belongs to a cycle if it can
+    /// be reached by following adoptions from `this`. If `this` does not
+    /// belong to a cycle, or if the cycle is kept alive by strong references
+    /// held outside of the cycle other than `this`, `None` is returned. Nodes
+    /// adopted by the cycle which have other owners are not counted.
+    ///
+    /// This function traverses the object graph and runs in `O(links + nodes)`
+    /// time if `this` has any adoptions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::{Adopt, Rc};
+    ///
+    /// let node = Rc::new(());
+    /// assert_eq!(Rc::cycle_len(&node), None);
+    ///
+    /// let clone = Rc::clone(&node);
+    /// unsafe {
+    ///     Rc::adopt_unchecked(&node, &clone);
+    /// }
+    /// assert_eq!(Rc::cycle_len(&node), Some(1));
+    ///
+    /// let external = Rc::clone(&node);
+    /// assert_eq!(Rc::cycle_len(&node), None);
+    /// # drop(external);
+    /// # Rc::unadopt(&node, &clone);
+    /// ```
+    #[must_use]
+    pub fn cycle_len(this: &Self) -> Option<usize> {
+        if !this.inner().has_links() {
+            return None;
+        }
+        let this = Link::forward(this.ptr);
+        if !reaches_itself(this) {
+            return None;
+        }
+        // Exclude the strong reference held by `this`.
+        let cycle = unowned_members(this, cycle_refs(this), 1);
+        if cycle.contains_key(&this) {
+            Some(cycle.len())
+        } else {
+            None
+        }
+    }
+
+    /// Returns the number of adoptions recorded by `this`, its out-degree in the
+    /// object graph.
+    ///
+    /// Each call to [`Adopt::adopt_unchecked`] with `this` as the adopting
+    /// `Rc` counts as one edge, including adoptions of `this` by itself.
+    /// Returns 0 if `this` has never adopted another `Rc`.
+    ///
+    /// [`Adopt::adopt_unchecked`]: crate::Adopt::adopt_unchecked
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::{Adopt, Rc};
+    ///
+    /// let parent = Rc::new(());
+    /// let child = Rc::new(());
+    /// let clone = Rc::clone(&child);
+    /// unsafe {
+    ///     Rc::adopt_unchecked(&parent, &clone);
+    /// }
+    ///
+    /// assert_eq!(Rc::out_degree(&parent), 1);
+    /// assert_eq!(Rc::out_degree(&child), 0);
+    /// # Rc::unadopt(&parent, &clone);
+    /// ```
+    #[must_use]
+    pub fn out_degree(this: &Self) -> usize {
+        this.inner().links().map_or(0, |links| {
+            links
+                .borrow()
+                .iter()
+                .filter(|(link, _)| matches!(link.kind(), Kind::Forward | Kind::Loopback))
+                .map(|(_, &count)| count)
+                .sum()
+        })
+    }
+
+    /// Returns the number of adoptions of `this` recorded by other `Rc`s, its
+    /// in-degree in the object graph.
+    ///
+    /// Each call to [`Adopt::adopt_unchecked`] with `this` as the adopted `Rc`
+    /// counts as one edge, including adoptions of `this` by itself. Returns 0
+    /// if `this` has never been adopted.
+    ///
+    /// [`Adopt::adopt_unchecked`]: crate::Adopt::adopt_unchecked
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::{Adopt, Rc};
+    ///
+    /// let parent = Rc::new(());
+    /// let child = Rc::new(());
+    /// let clone = Rc::clone(&child);
+    /// unsafe {
+    ///     Rc::adopt_unchecked(&parent, &clone);
+    /// }
+    ///
+    /// assert_eq!(Rc::in_degree(&parent), 0);
+    /// assert_eq!(Rc::in_degree(&child), 1);
+    /// # Rc::unadopt(&parent, &clone);
+    /// ```
+    #[must_use]
+    pub fn in_degree(this: &Self) -> usize {
+        this.inner().links().map_or(0, |links| {
+            links
+                .borrow()
+                .iter()
+                .filter(|(link, _)| matches!(link.kind(), Kind::Backward | Kind::Loopback))
+                .map(|(_, &count)| count)
+                .sum()
+        })
+    }
+
+    /// Returns whether `this` is linked into an object graph.
+    ///
+    /// An `Rc` is graphed if it has adopted or been adopted by any `Rc`,
+    /// including itself. An `Rc` returns to being ungraphed once all of its
+    /// adoptions are removed with [`Adopt::unadopt`].
+    ///
+    /// This function does not traverse the object graph.
+    ///
+    /// [`Adopt::unadopt`]: crate::Adopt::unadopt
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::{Adopt, Rc};
+    ///
+    /// let parent = Rc::new(());
+    /// let child = Rc::new(());
+    /// assert!(!Rc::is_graphed(&parent));
+    ///
+    /// let clone = Rc::clone(&child);
+    /// unsafe {
+    ///     Rc::adopt_unchecked(&parent, &clone);
+    /// }
+    /// assert!(Rc::is_graphed(&parent));
+    /// assert!(Rc::is_graphed(&child));
+    ///
+    /// Rc::unadopt(&parent, &clone);
+    /// assert!(!Rc::is_graphed(&parent));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_graphed(this: &Self) -> bool {
+        this.inner().has_links()
+    }
+
+    /// Returns the number of adoptions recorded in the object graph `this`
+    /// belongs to, or 0 if `this` is not graphed.
+    ///
+    /// The object graph includes every `Rc` reachable from `this` by following
+    /// adoptions in either direction. Each call to [`Adopt::adopt_unchecked`]
+    /// between members of the graph counts as one edge.
+    ///
+    /// This function traverses the object graph and runs in `O(links + nodes)`
+    /// time if `this` has any adoptions.
+    ///
+    /// [`Adopt::adopt_unchecked`]: crate::Adopt::adopt_unchecked
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::{Adopt, Rc};
+    ///
+    /// let parent = Rc::new(());
+    /// let child = Rc::new(());
+    /// let grandchild = Rc::new(());
+    /// assert_eq!(Rc::graph_size(&parent), 0);
+    ///
+    /// let child_clone = Rc::clone(&child);
+    /// let grandchild_clone = Rc::clone(&grandchild);
+    /// unsafe {
+    ///     Rc::adopt_unchecked(&parent, &child_clone);
+    ///     Rc::adopt_unchecked(&child, &grandchild_clone);
+    /// }
+    /// assert_eq!(Rc::graph_size(&parent), 2);
+    /// assert_eq!(Rc::graph_size(&grandchild), 2);
+    /// # Rc::unadopt(&parent, &child_clone);
+    /// # Rc::unadopt(&child, &grandchild_clone);
+    /// ```
+    #[must_use]
+    pub fn graph_size(this: &Self) -> usize {
+        if !this.inner().has_links() {
+            return 0;
+        }
+        graph_edges(&graph_nodes(Link::forward(this.ptr)))
+    }
+
+    /// Returns an estimate of the number of bytes allocated for the object
+    /// graph `this` belongs to.
+    ///
+    /// The estimate includes the allocation of every member of the graph, as
+    /// found by [`Rc::graph_nodes`], and the adoption links they use to record
+    /// the graph. It does not include heap memory owned by the values, like
+    /// the buffer of a `String`. The size of the hash tables which store
+    /// adoption links is estimated from their capacity.
+    ///
+    /// If `this` is not graphed, the size of its allocation is returned.
+    ///
+    /// This function traverses the object graph and runs in `O(links + nodes)`
+    /// time if `this` has any adoptions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::{Adopt, Rc};
+    ///
+    /// let first = Rc::new([0_u8; 64]);
+    /// let second = Rc::new([0_u8; 64]);
+    /// let ungraphed = Rc::graph_heap_size(&first);
+    /// assert!(ungraphed >= 64);
+    ///
+    /// let clone = Rc::clone(&second);
+    /// unsafe {
+    ///     Rc::adopt_unchecked(&first, &clone);
+    /// }
+    /// assert!(Rc::graph_heap_size(&first) > 2 * ungraphed);
+    /// assert_eq!(Rc::graph_heap_size(&first), Rc::graph_heap_size(&second));
+    /// # Rc::unadopt(&first, &clone);
+    /// ```
+    #[must_use]
+    pub fn graph_heap_size(this: &Self) -> usize {
+        graph_nodes(Link::forward(this.ptr))
+            .iter()
+            .map(|node| {
+                let links = node.as_ref().links().map_or(0, |links| {
+                    size_of::<RefCell<Links<T>>>() + links.borrow().heap_size()
+                });
+                size_of::<RcBox<T>>() + links
+            })
+            .sum()
+    }
+
+    /// Returns the number of adoption links the members of the object graph
+    /// `this` belongs to can record without reallocating.
+    ///
+    /// Each member stores its adoption links in its own hash table. Every
+    /// adoption takes one entry in the adopter and one in the adoptee, and
+    /// repeated adoptions of the same `Rc` share an entry. Removing links does
+    /// not release the memory of a hash table, so comparing the capacity with
+    /// [`Rc::graph_size`] can be used to decide whether to call
+    /// [`Rc::shrink_graph`].
+    ///
+    /// This function is intended for debugging. It traverses the object graph
+    /// and runs in `O(nodes)` time if `this` has any adoptions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::{Adopt, Rc};
+    ///
+    /// let parent = Rc::new(());
+    /// assert_eq!(Rc::graph_capacity(&parent), 0);
+    ///
+    /// let child = Rc::new(());
+    /// let clone = Rc::clone(&child);
+    /// unsafe {
+    ///     Rc::adopt_unchecked(&parent, &clone);
+    /// }
+    /// assert!(Rc::graph_capacity(&parent) >= 2);
+    /// # Rc::unadopt(&parent, &clone);
+    /// ```
+    #[must_use]
+    pub fn graph_capacity(this: &Self) -> usize {
+        graph_nodes(Link::forward(this.ptr))
+            .iter()
+            .filter_map(|node| node.as_ref().links())
+            .map(|links| links.borrow().capacity())
+            .sum()
+    }
+
+    /// Shrinks the adoption links of every member of the object graph `this`
+    /// belongs to so they hold no more capacity than needed.
+    ///
+    /// The adoption links of an `Rc` keep their memory after adoptions are
+    /// removed with [`Adopt::unadopt`]. Shrinking reclaims this memory for
+    /// long-lived object graphs that have grown and then shrunk.
+    ///
+    /// Only members of the object graph are shrunk. `Rc`s which were detached
+    /// from the graph by unadopting them are not reachable from `this` and
+    /// keep their capacity until they are adopted again or dropped.
+    ///
+    /// This function traverses the object graph and runs in `O(links + nodes)`
+    /// time if `this` has any adoptions.
+    ///
+    /// [`Adopt::unadopt`]: crate::Adopt::unadopt
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::{Adopt, Rc};
+    ///
+    /// let head = Rc::new(0);
+    /// let nodes = Rc::new_many(1..=100);
+    /// for node in &nodes {
+    ///     unsafe {
+    ///         Rc::adopt_unchecked(&head, node);
+    ///     }
+    /// }
+    /// for node in &nodes[1..] {
+    ///     Rc::unadopt(&head, node);
+    /// }
+    ///
+    /// let capacity = Rc::graph_capacity(&head);
+    /// Rc::shrink_graph(&head);
+    /// assert!(Rc::graph_capacity(&head) < capacity);
+    /// # Rc::unadopt(&head, &nodes[0]);
+    /// ```
+    pub fn shrink_graph(this: &Self) {
+        for node in graph_nodes(Link::forward(this.ptr)) {
+            if let Some(links) = node.as_ref().links() {
+                links.borrow_mut().shrink_to_fit();
+            }
+        }
+    }
+
+    /// Returns a strong reference to every member of the object graph `this`
+    /// belongs to, including `this`.
+    ///
+    /// The object graph includes every `Rc` reachable from `this` by following
+    /// adoptions in either direction, like [`Rc::graph_size`]. Each member is
+    /// returned once, in an unspecified order. If `this` is not graphed, only
+    /// `this` is returned.
+    ///
+    /// This is intended for debugging and inspection. The returned `Rc`s are
+    /// external owners of the graph, so it is not collected while they are
+    /// alive.
+    ///
+    /// This function traverses the object graph and runs in `O(links + nodes)`
+    /// time if `this` has any adoptions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::{Adopt, Rc};
+    ///
+    /// let parent = Rc::new("parent");
+    /// let child = Rc::new("child");
+    /// assert_eq!(Rc::graph_nodes(&parent).len(), 1);
+    ///
+    /// let clone = Rc::clone(&child);
+    /// unsafe {
+    ///     Rc::adopt_unchecked(&parent, &clone);
+    /// }
+    /// let mut nodes = Rc::graph_nodes(&child)
+    ///     .iter()
+    ///     .map(|node| **node)
+    ///     .collect::<Vec<_>>();
+    /// nodes.sort_unstable();
+    /// assert_eq!(nodes, ["child", "parent"]);
+    /// # Rc::unadopt(&parent, &clone);
+    /// ```
+    #[must_use]
+    pub fn graph_nodes(this: &Self) -> Vec<Rc<T>> {
+        if !this.inner().has_links() {
+            return vec![Rc::clone(this)];
+        }
+        graph_nodes(Link::forward(this.ptr))
+            .into_iter()
+            // Members of a cycle which is being collected are dead and must not
+            // be resurrected.
+            .filter(|node| !node.as_ref().is_dead())
+            .map(|node| {
+                node.as_ref().inc_strong();
+                Rc::from_inner(node.into_raw_non_null())
+            })
+            .collect()
+    }
+
+    /// Returns whether `a` and `b` belong to the same object graph.
+    ///
+    /// The object graph includes every `Rc` reachable from `a` by following
+    /// adoptions in either direction, like [`Rc::graph_nodes`]. `Rc`s which
+    /// point to the same allocation are always in the same object graph. An
+    /// `Rc` which is not graphed is only in the same object graph as itself.
+    ///
+    /// Every `Rc` stores its own adoption links, so membership is determined
+    /// by traversing the object graph of `a`. This function runs in
+    /// `O(links + nodes)` time if both `a` and `b` have any adoptions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::{Adopt, Rc};
+    ///
+    /// let first = Rc::new(1);
+    /// let second = Rc::new(2);
+    /// let third = Rc::new(3);
+    /// let second_clone = Rc::clone(&second);
+    /// let third_clone = Rc::clone(&third);
+    /// unsafe {
+    ///     Rc::adopt_unchecked(&first, &second_clone);
+    /// }
+    /// assert!(Rc::same_graph(&first, &second));
+    /// assert!(!Rc::same_graph(&first, &third));
+    ///
+    /// // Adopting `third` from `second` joins it to the graph of `first`.
+    /// unsafe {
+    ///     Rc::adopt_unchecked(&second, &third_clone);
+    /// }
+    /// assert!(Rc::same_graph(&third, &first));
+    /// # Rc::unadopt(&first, &second_clone);
+    /// # Rc::unadopt(&second, &third_clone);
+    /// ```
+    #[must_use]
+    pub fn same_graph(a: &Self, b: &Self) -> bool {
+        if Rc::ptr_eq(a, b) {
+            return true;
+        }
+        if !a.inner().has_links() || !b.inner().has_links() {
+            return false;
+        }
+        graph_nodes(Link::forward(a.ptr)).contains(&Link::forward(b.ptr))
+    }
+
+    /// Returns whether `target` is reachable from any of `roots` by following
+    /// adoptions.
+    ///
+    /// An `Rc` is reachable from a root if it is the root or if there is a
+    /// path of adoptions from the root to it, i.e. the root transitively owns
+    /// a strong reference to it. Strong references which were not recorded
+    /// with [`Adopt::adopt_unchecked`] are not followed.
+    ///
+    /// This can be used for manual root analysis of an object graph, for
+    /// example to determine whether any of a set of roots keeps a node alive.
+    ///
+    /// This function traverses the object graph and runs in `O(links + nodes)`
+    /// time. Nodes reachable from more than one root are visited once.
+    ///
+    /// [`Adopt::adopt_unchecked`]: crate::Adopt::adopt_unchecked
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::{Adopt, Rc};
+    ///
+    /// let root = Rc::new(());
+    /// let child = Rc::new(());
+    /// let other = Rc::new(());
+    /// let clone = Rc::clone(&child);
+    /// unsafe {
+    ///     Rc::adopt_unchecked(&root, &clone);
+    /// }
+    ///
+    /// assert!(Rc::reachable_from_any(&child, &[Rc::clone(&root)]));
+    /// assert!(!Rc::reachable_from_any(&root, &[Rc::clone(&child)]));
+    /// assert!(!Rc::reachable_from_any(&other, &[root.clone()]));
+    /// # Rc::unadopt(&root, &clone);
+    /// ```
+    #[must_use]
+    pub fn reachable_from_any(target: &Self, roots: &[Rc<T>]) -> bool {
+        let target = Link::forward(target.ptr);
+        let mut discovered = roots
+            .iter()
+            .map(|root| Link::forward(root.ptr))
+            .collect::<Vec<_>>();
+        let mut visited = HashSet::default();
+
+        while let Some(node) = discovered.pop() {
+            if node == target {
+                return true;
+            }
+            if !visited.insert(node) {
+                continue;
+            }
+            let Some(links) = node.as_ref().links() else {
+                continue;
+            };
+            let links = links.borrow();
+            let owned = links
+                .iter()
+                .filter(|(link, _)| link.kind() == Kind::Forward)
+                .map(|(&link, _)| link);
+            discovered.extend(owned.filter(|link| !visited.contains(link)));
+        }
+        false
+    }
+
+    /// Checks the adoption bookkeeping of the object graph `this` belongs to.
+    ///
+    /// This is a debugging aid for fuzzers and test harnesses which use
+    /// [`Adopt::adopt_unchecked`] and [`Adopt::unadopt`] directly. The
+    /// following invariants are checked for every node reachable from `this`
+    /// by following adoptions in either direction:
+    ///
+    /// - Every adoption has a matching back reference in the adopted node, and
+    ///   every back reference has a matching adoption.
+    /// - No node links to a node whose value has been dropped.
+    /// - No node is adopted more times than it has strong references.
+    ///
+    /// The first violation found is returned. Links to deallocated memory
+    /// cannot be detected.
+    ///
+    /// This function is only available in builds with debug assertions
+    /// enabled. It traverses the object graph and runs in `O(links + nodes)`
+    /// time.
+    ///
+    /// [`Adopt::adopt_unchecked`]: crate::Adopt::adopt_unchecked
+    /// [`Adopt::unadopt`]: crate::Adopt::unadopt
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`GraphInvariantViolation`] describing the first broken
+    /// invariant.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # cactusref::__debug_assertions! {
+    /// use cactusref::{Adopt, GraphInvariantViolation, Rc};
+    ///
+    /// let parent = Rc::new(());
+    /// let child = Rc::new(());
+    /// let clone = Rc::clone(&child);
+    /// unsafe {
+    ///     Rc::adopt_unchecked(&parent, &clone);
+    /// }
+    /// assert_eq!(Rc::validate_graph(&parent), Ok(()));
+    ///
+    /// // These adoptions are not backed by strong references owned by `parent`.
+    /// unsafe {
+    ///     Rc::adopt_unchecked(&parent, &child);
+    ///     Rc::adopt_unchecked(&parent, &child);
+    /// }
+    /// assert_eq!(
+    ///     Rc::validate_graph(&child),
+    ///     Err(GraphInvariantViolation::AdoptionsExceedStrongCount {
+    ///         node: Rc::as_ptr(&child) as usize,
+    ///         adoptions: 3,
+    ///         strong: 2,
+    ///     })
+    /// );
+    /// # Rc::unadopt(&parent, &child);
+    /// # Rc::unadopt(&parent, &child);
+    /// # Rc::unadopt(&parent, &clone);
+    /// # }
+    /// ```
+    #[cfg(debug_assertions)]
+    pub fn validate_graph(this: &Self) -> Result<(), GraphInvariantViolation> {
+        if this.inner().is_dead() {
+            return Ok(());
+        }
+        let mut discovered = vec![Link::forward(this.ptr)];
+        let mut visited = HashSet::default();
+        let mut adoptions = HashMap::<Link<T>, usize>::default();
+
+        while let Some(node) = discovered.pop() {
+            if !visited.insert(node) {
+                continue;
+            }
+            let Some(links) = node.as_ref().links() else {
+                continue;
+            };
+            for (link, &count) in links.borrow().iter() {
+                let other = link.as_forward();
+                if other.as_ref().is_dead() {
+                    return Err(GraphInvariantViolation::DeadLink {
+                        from: value_addr(node),
+                        to: value_addr(other),
+                    });
+                }
+                match link.kind() {
+                    Kind::Forward => {
+                        if link_count(other, Link::backward(node.into_raw_non_null())) != count {
+                            return Err(GraphInvariantViolation::UnmatchedForwardLink {
+                                from: value_addr(node),
+                                to: value_addr(other),
+                            });
+                        }
+                        *adoptions.entry(other).or_default() += count;
+                    }
+                    Kind::Backward => {
+                        if link_count(other, Link::forward(node.into_raw_non_null())) != count {
+                            return Err(GraphInvariantViolation::UnmatchedBackwardLink {
+                                from: value_addr(other),
+                                to: value_addr(node),
+                            });
+                        }
+                    }
+                    Kind::Loopback => *adoptions.entry(other).or_default() += count,
+                }
+                if !visited.contains(&other) {
+                    discovered.push(other);
+                }
+            }
+        }
+
+        for (node, adoptions) in adoptions {
+            let strong = node.strong();
+            if adoptions > strong {
+                return Err(GraphInvariantViolation::AdoptionsExceedStrongCount {
+                    node: value_addr(node),
+                    adoptions,
+                    strong,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Asserts that the object graph `this` belongs to has no cycles.
+    ///
+    /// Some data structures use adoptions for shared ownership but must remain
+    /// a tree or a directed acyclic graph. This function checks whether any
+    /// path of adoptions in the object graph leads back to where it started,
+    /// including an `Rc` which adopts its own allocation.
+    ///
+    /// The object graph includes every `Rc` reachable from `this` by following
+    /// adoptions in either direction, like [`Rc::graph_size`], so cycles which
+    /// `this` is not a member of are also reported.
+    ///
+    /// This function traverses the object graph and runs in `O(links + nodes)`
+    /// time if `this` has any adoptions. It is only available in debug builds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the object graph has a cycle. The panic message includes the
+    /// addresses of the values of the adopting and adopted `Rc` of an adoption
+    /// which closes the cycle, as returned by [`Rc::as_ptr`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # cactusref::__debug_assertions! {
+    /// use cactusref::{Adopt, Rc};
+    ///
+    /// let root = Rc::new(());
+    /// let left = Rc::new(());
+    /// let right = Rc::new(());
+    /// let leaf = Rc::new(());
+    /// let clones = [&left, &right, &leaf, &leaf].map(Rc::clone);
+    /// unsafe {
+    ///     Rc::adopt_unchecked(&root, &clones[0]);
+    ///     Rc::adopt_unchecked(&root, &clones[1]);
+    ///     Rc::adopt_unchecked(&left, &clones[2]);
+    ///     Rc::adopt_unchecked(&right, &clones[3]);
+    /// }
+    ///
+    /// // A diamond shares `leaf` but has no cycles.
+    /// Rc::assert_acyclic(&leaf);
+    /// # Rc::unadopt(&root, &clones[0]);
+    /// # Rc::unadopt(&root, &clones[1]);
+    /// # Rc::unadopt(&left, &clones[2]);
+    /// # Rc::unadopt(&right, &clones[3]);
+    /// # }
+    /// ```
+    #[cfg(debug_assertions)]
+    #[track_caller]
+    pub fn assert_acyclic(this: &Self) {
+        if this.inner().is_dead() || !this.inner().has_links() {
+            return;
+        }
+        if let Some((from, to)) = back_edge(Link::forward(this.ptr)) {
+            panic!("cactusref object graph has a cycle: adoption of {to:#x} by {from:#x} closes a cycle");
+        }
+    }
+
     /// Traverse the linked object graph from the given `Rc` to determine if the
     /// graph is not externally reachable.
     ///
@@ -20,25 +785,85 @@ impl<T> Rc<T> {
     ///
     /// This function is invoked during `drop` to determine which strategy to use
     /// for deallocating a group of `Rc`s.
+    ///
+    /// If the traversal visits more nodes than the [trace budget], the graph is
+    /// conservatively assumed to be externally reachable and `None` is
+    /// returned.
+    ///
+    /// [trace budget]: crate::set_trace_budget
     pub(crate) fn orphaned_cycle(this: &Self) -> Option<HashMap<Link<T>, usize>> {
-        let cycle = cycle_refs(Link::forward(this.ptr));
-        if cycle.is_empty() {
+        let this = Link::forward(this.ptr);
+        #[cfg(feature = "std")]
+        let max_nodes = trace_budget();
+        #[cfg(not(feature = "std"))]
+        let max_nodes = usize::MAX;
+        let Some(cycle_refs) = bounded_cycle_refs(this, max_nodes) else {
+            warn!(
+                "cactusref drop exceeded trace budget of {max_nodes} nodes, leaking object graph"
+            );
             return None;
-        }
-        let has_external_owners = cycle
-            .iter()
-            .any(|(item, &cycle_owned_refs)| item.strong() > cycle_owned_refs);
-        if has_external_owners {
-            None
-        } else {
+        };
+        let cycle = unowned_members(this, cycle_refs, 0);
+        if cycle.contains_key(&this) {
             Some(cycle)
+        } else {
+            None
         }
     }
 }
 
+// Perform a breadth first search over all of the forward and backward links to
+// collect every node in the object graph `this` belongs to.
+//
+// Nodes are keyed by their forward link.
+pub(crate) fn graph_nodes<T>(this: Link<T>) -> HashSet<Link<T>> {
+    let mut discovered = vec![this.as_forward()];
+    let mut visited = HashSet::default();
+
+    while let Some(node) = discovered.pop() {
+        if !visited.insert(node) {
+            continue;
+        }
+        let Some(links) = node.as_ref().links() else {
+            continue;
+        };
+        for link in links.borrow().iter().map(|(link, _)| link.as_forward()) {
+            if !visited.contains(&link) {
+                discovered.push(link);
+            }
+        }
+    }
+    visited
+}
+
+// Count the adoptions recorded by the given nodes of an object graph.
+pub(crate) fn graph_edges<T>(nodes: &HashSet<Link<T>>) -> usize {
+    nodes
+        .iter()
+        .filter_map(|node| node.as_ref().links())
+        .map(|links| {
+            links
+                .borrow()
+                .iter()
+                .filter(|(link, _)| matches!(link.kind(), Kind::Forward | Kind::Loopback))
+                .map(|(_, &count)| count)
+                .sum::<usize>()
+        })
+        .sum()
+}
+
 // Perform a breadth first search over all of the forward and backward links to
 // determine the clique of nodes in a cycle and their strong counts.
 fn cycle_refs<T>(this: Link<T>) -> HashMap<Link<T>, usize> {
+    match bounded_cycle_refs(this, usize::MAX) {
+        Some(cycle_owned_refs) => cycle_owned_refs,
+        None => unreachable!("object graph has more than usize::MAX nodes"),
+    }
+}
+
+// Like `cycle_refs`, but give up and return `None` once more than `max_nodes`
+// nodes have been visited.
+fn bounded_cycle_refs<T>(this: Link<T>, max_nodes: usize) -> Option<HashMap<Link<T>, usize>> {
     // These collections track compute the layout of the object graph in linear
     // time in the size of the graph.
     let mut cycle_owned_refs = HashMap::default();
@@ -50,25 +875,158 @@ fn cycle_refs<T>(this: Link<T>) -> HashMap<Link<T>, usize> {
         if visited.contains(&node) {
             continue;
         }
+        if visited.len() == max_nodes {
+            return None;
+        }
         visited.insert(node);
 
-        let links = unsafe { node.as_ref().links().borrow() };
-        for (&link, &strong) in links.iter() {
-            if let Kind::Forward | Kind::Loopback = link.kind() {
-                cycle_owned_refs
-                    .entry(link)
-                    .and_modify(|count| *count += strong)
-                    .or_insert(strong);
-                discovered.push(link);
-            } else {
-                cycle_owned_refs.entry(link.as_forward()).or_default();
+        let Some(links) = node.as_ref().links() else {
+            continue;
+        };
+        for (&link, &strong) in links.borrow().iter() {
+            match link.kind() {
+                Kind::Forward => {
+                    cycle_owned_refs
+                        .entry(link)
+                        .and_modify(|count| *count += strong)
+                        .or_insert(strong);
+                    discovered.push(link);
+                }
+                Kind::Backward => {
+                    cycle_owned_refs.entry(link.as_forward()).or_default();
+                }
+                // Adopting an `Rc` with itself does not record an owned strong
+                // reference.
+                Kind::Loopback => {}
             }
         }
     }
 
     #[cfg(debug_assertions)]
     debug_cycle(&cycle_owned_refs);
-    cycle_owned_refs
+    Some(cycle_owned_refs)
+}
+
+// Remove the members of `cycle` which have strong references held outside of
+// the cycle, and every member they transitively adopt.
+//
+// The remaining members are only kept alive by each other. Members which are
+// adopted by the cycle but owned elsewhere do not keep the cycle alive.
+//
+// `released` strong references to `this` are not counted as external owners.
+fn unowned_members<T>(
+    this: Link<T>,
+    mut cycle: HashMap<Link<T>, usize>,
+    released: usize,
+) -> HashMap<Link<T>, usize> {
+    let mut discovered = cycle
+        .iter()
+        .filter(|&(item, &cycle_owned_refs)| {
+            let strong = if *item == this {
+                item.strong().saturating_sub(released)
+            } else {
+                item.strong()
+            };
+            strong > cycle_owned_refs
+        })
+        .map(|(&item, _)| item)
+        .collect::<Vec<_>>();
+
+    while let Some(node) = discovered.pop() {
+        if cycle.remove(&node).is_none() {
+            continue;
+        }
+        let Some(links) = node.as_ref().links() else {
+            continue;
+        };
+        let links = links.borrow();
+        let owned = links
+            .iter()
+            .filter(|(link, _)| link.kind() == Kind::Forward)
+            .map(|(&link, _)| link);
+        discovered.extend(owned.filter(|link| cycle.contains_key(link)));
+    }
+    cycle
+}
+
+// Perform a depth first search over the adoptions reachable from `this` to
+// determine whether `this` is a member of a cycle.
+fn reaches_itself<T>(this: Link<T>) -> bool {
+    let mut visited = HashSet::default();
+    let mut discovered = adoptions(this);
+    while let Some(node) = discovered.pop() {
+        if node == this {
+            return true;
+        }
+        if visited.insert(node) {
+            discovered.extend(adoptions(node));
+        }
+    }
+    false
+}
+
+// Perform a depth first search over the adoptions of every node in the object
+// graph `this` belongs to and return the addresses of the adopting and adopted
+// values of an adoption which closes a cycle, if any.
+#[cfg(debug_assertions)]
+fn back_edge<T>(this: Link<T>) -> Option<(usize, usize)> {
+    // Nodes map to `false` while they are on the DFS stack and `true` once all
+    // of their adoptions have been searched.
+    let mut finished = HashMap::<Link<T>, bool>::default();
+    for root in graph_nodes(this) {
+        if finished.contains_key(&root) {
+            continue;
+        }
+        finished.insert(root, false);
+        let mut stack = vec![(root, adoptions(root))];
+        while let Some((node, children)) = stack.last_mut() {
+            let node = *node;
+            let Some(child) = children.pop() else {
+                finished.insert(node, true);
+                stack.pop();
+                continue;
+            };
+            match finished.get(&child) {
+                Some(false) => return Some((value_addr(node), value_addr(child))),
+                Some(true) => {}
+                None => {
+                    finished.insert(child, false);
+                    stack.push((child, adoptions(child)));
+                }
+            }
+        }
+    }
+    None
+}
+
+// The forward links of the `Rc`s adopted by `node`, including `node` itself if
+// it has adopted its own allocation.
+fn adoptions<T>(node: Link<T>) -> Vec<Link<T>> {
+    let Some(links) = node.as_ref().links() else {
+        return Vec::new();
+    };
+    let links = links.borrow();
+    links
+        .iter()
+        .filter(|(link, _)| matches!(link.kind(), Kind::Forward | Kind::Loopback))
+        .map(|(link, _)| link.as_forward())
+        .collect()
+}
+
+// The number of times `node` recorded `link`.
+#[cfg(debug_assertions)]
+fn link_count<T>(node: Link<T>, link: Link<T>) -> usize {
+    node.as_ref()
+        .links()
+        .and_then(|links| links.borrow().get(link))
+        .unwrap_or_default()
+}
+
+// The address of the value of `node`, as returned by `Rc::as_ptr`.
+#[cfg(debug_assertions)]
+pub(crate) fn value_addr<T>(node: Link<T>) -> usize {
+    // SAFETY: `node` points to a live allocation. The value is not read.
+    unsafe { ptr::addr_of!((*node.as_ptr()).value) as usize }
 }
 
 #[cfg(debug_assertions)]
@@ -90,13 +1048,11 @@ fn debug_cycle<T>(cycle: &HashMap<Link<T>, usize>) {
 
     if has_external_owners {
         trace!(
-            "cactusref reachability test found externally owned cycle with (strong, cycle) counts: {:?}",
-            counts
+            "cactusref reachability test found externally owned cycle with (strong, cycle) counts: {counts:?}"
         );
     } else {
         trace!(
-            "cactusref reachability test found unreachable cycle  with (strong, cycle) counts: {:?}",
-            counts
+            "cactusref reachability test found unreachable cycle  with (strong, cycle) counts: {counts:?}"
         );
     }
 }
@@ -6,6 +6,9 @@
 //! The following implements a doubly-linked list that is fully deallocated once
 //! the `list` binding is dropped.
 //!
+//! A ready-made list which encapsulates this bookkeeping is available as
+//! [`collections::LinkedList`](crate::collections::LinkedList).
+//!
 //! ```rust
 //! use std::cell::RefCell;
 //! use std::iter;
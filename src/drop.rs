@@ -1,13 +1,27 @@
 use alloc::alloc::{Allocator, Global, Layout};
+#[cfg(feature = "cycle-detection")]
 use alloc::vec;
+#[cfg(all(feature = "std", feature = "cycle-detection"))]
+use core::cmp::Reverse;
 use core::mem::{self, MaybeUninit};
-use core::ptr;
+#[cfg(feature = "cycle-detection")]
+use core::ptr::{self, NonNull};
 
-#[cfg(doc)]
+#[cfg(all(doc, feature = "cycle-detection"))]
 use crate::adopt::Adopt;
+#[cfg(all(feature = "std", feature = "cycle-detection"))]
+use crate::collector;
+#[cfg(all(feature = "std", feature = "cycle-detection"))]
+use crate::config::{drop_order, DropOrder};
+#[cfg(all(debug_assertions, feature = "cycle-detection"))]
+use crate::cycle::value_addr;
+#[cfg(feature = "cycle-detection")]
 use crate::hash::HashMap;
+#[cfg(feature = "cycle-detection")]
 use crate::link::{Kind, Link};
-use crate::rc::RcInnerPtr;
+#[cfg(feature = "cycle-detection")]
+use crate::rc::RcBox;
+use crate::rc::{RcInnerPtr, RcState};
 use crate::Rc;
 
 unsafe impl<#[may_dangle] T> Drop for Rc<T> {
@@ -27,7 +41,14 @@ unsafe impl<#[may_dangle] T> Drop for Rc<T> {
     /// `Rc`s do not pay the cost of the reachability check unless they use
     /// [`Adopt::adopt_unchecked`].
     ///
-    /// [`Adopt::adopt_unchecked`]: crate::Adopt::adopt_unchecked
+    #[cfg_attr(
+        feature = "cycle-detection",
+        doc = "[`Adopt::adopt_unchecked`]: crate::Adopt::adopt_unchecked"
+    )]
+    #[cfg_attr(
+        not(feature = "cycle-detection"),
+        doc = "[`Adopt::adopt_unchecked`]: https://docs.rs/cactusref/latest/cactusref/trait.Adopt.html#tymethod.adopt_unchecked"
+    )]
     ///
     /// # Examples
     ///
@@ -50,6 +71,8 @@ unsafe impl<#[may_dangle] T> Drop for Rc<T> {
     /// ```
     ///
     /// ```
+    /// # #[cfg(feature = "cycle-detection")]
+    /// # fn main() {
     /// use cactusref::{Adopt, Rc};
     ///
     /// struct Foo(u8);
@@ -70,6 +93,9 @@ unsafe impl<#[may_dangle] T> Drop for Rc<T> {
     ///
     /// drop(foo);    // Doesn't print anything
     /// drop(foo2);   // Prints "dropped 10!" and "dropped 20!"
+    /// # }
+    /// # #[cfg(not(feature = "cycle-detection"))]
+    /// # fn main() {}
     /// ```
     ///
     /// # Cycle Detection and Deallocation Algorithm
@@ -78,7 +104,14 @@ unsafe impl<#[may_dangle] T> Drop for Rc<T> {
     /// adoptee `Rc`s.  These links form a graph of reachable objects which are
     /// used to detect cycles.
     ///
-    /// [`Rc::adopt_unchecked`]: crate::Rc::adopt_unchecked
+    #[cfg_attr(
+        feature = "cycle-detection",
+        doc = "[`Rc::adopt_unchecked`]: crate::Rc::adopt_unchecked"
+    )]
+    #[cfg_attr(
+        not(feature = "cycle-detection"),
+        doc = "[`Rc::adopt_unchecked`]: https://docs.rs/cactusref/latest/cactusref/struct.Rc.html#method.adopt_unchecked"
+    )]
     ///
     /// On drop, if an `Rc` has no links, it is dropped like a normal `Rc`. If
     /// the `Rc` has links, `Drop` performs a breadth first search by traversing
@@ -86,8 +119,22 @@ unsafe impl<#[may_dangle] T> Drop for Rc<T> {
     /// requires correct use of [`Adopt::adopt_unchecked`] and [`Adopt::unadopt`]
     /// to perform the reachability bookkeeping.
     ///
-    /// [`Adopt::adopt_unchecked`]: crate::Adopt::adopt_unchecked
-    /// [`Adopt::unadopt`]: crate::Adopt::unadopt
+    #[cfg_attr(
+        feature = "cycle-detection",
+        doc = "[`Adopt::adopt_unchecked`]: crate::Adopt::adopt_unchecked"
+    )]
+    #[cfg_attr(
+        not(feature = "cycle-detection"),
+        doc = "[`Adopt::adopt_unchecked`]: https://docs.rs/cactusref/latest/cactusref/trait.Adopt.html#tymethod.adopt_unchecked"
+    )]
+    #[cfg_attr(
+        feature = "cycle-detection",
+        doc = "[`Adopt::unadopt`]: crate::Adopt::unadopt"
+    )]
+    #[cfg_attr(
+        not(feature = "cycle-detection"),
+        doc = "[`Adopt::unadopt`]: https://docs.rs/cactusref/latest/cactusref/trait.Adopt.html#tymethod.unadopt"
+    )]
     ///
     /// After determining all reachable objects, `Rc` reduces the graph to
     /// objects that form a cycle by performing pairwise reachability checks.
@@ -100,6 +147,44 @@ unsafe impl<#[may_dangle] T> Drop for Rc<T> {
     /// If the cycle is orphaned, `Rc` busts all the link structures and
     /// deallocates each object.
     ///
+    /// All links in an orphaned cycle are torn down before any of the inner
+    /// values are dropped. If the [`Drop`] implementation of an inner value
+    /// calls [`Adopt::adopt_unchecked`] or [`Adopt::unadopt`] on members of
+    /// the cycle being collected, these bookkeeping changes are ignored.
+    ///
+    /// If deferred collection is enabled with [`collector::defer`], dropping
+    /// an `Rc` which belongs to an object graph does not trace the graph. The
+    /// allocation is enqueued on a thread-local work list and its graph is
+    /// traced and deallocated, if orphaned, by [`collector::collect`].
+    ///
+    #[cfg_attr(
+        all(feature = "std", feature = "cycle-detection"),
+        doc = "[`collector::defer`]: crate::collector::defer"
+    )]
+    #[cfg_attr(
+        not(all(feature = "std", feature = "cycle-detection")),
+        doc = "[`collector::defer`]: https://docs.rs/cactusref/latest/cactusref/collector/fn.defer.html"
+    )]
+    #[cfg_attr(
+        all(feature = "std", feature = "cycle-detection"),
+        doc = "[`collector::collect`]: crate::collector::collect"
+    )]
+    #[cfg_attr(
+        not(all(feature = "std", feature = "cycle-detection")),
+        doc = "[`collector::collect`]: https://docs.rs/cactusref/latest/cactusref/collector/fn.collect.html"
+    )]
+    ///
+    /// If the [`Drop`] implementation of an inner value panics while an
+    /// orphaned cycle is being collected, the remaining inner values are
+    /// still dropped and every member of the cycle is deallocated before the
+    /// panic propagates. A second panic while unwinding aborts the process.
+    ///
+    /// In debug builds, dropping an orphaned cycle panics if a member of the
+    /// cycle has been adopted by other members more times than it has strong
+    /// references, which means an [`Adopt::unadopt`] call is missing or an
+    /// adoption is not backed by an owned strong reference. The panic message
+    /// lists the adopters of each unbalanced member. The cycle is leaked.
+    ///
     /// ## Performance
     ///
     /// Cycle detection uses breadth first search to trace the object graph.
@@ -131,60 +216,66 @@ unsafe impl<#[may_dangle] T> Drop for Rc<T> {
         unsafe {
             // If links is empty, the object is either not in a cycle or
             // part of a cycle that has been link busted for deallocation.
-            if self.inner().links().borrow().is_empty() {
-                // If the object was never in a cycle, `dec_strong` above will
-                // kill the `Rc`.
-                //
-                // If the object was in a cycle, the `Rc` will only be dead if
-                // all strong references to it have been dropped.
+            //
+            // Without the `cycle-detection` feature, there are no links and
+            // `Rc`s are dropped like `std::rc::Rc`.
+            #[cfg(feature = "cycle-detection")]
+            if self.inner().has_links() {
                 if self.inner().is_dead() {
-                    drop_unreachable(self);
+                    drop_unreachable_with_adoptions(self);
+                    return;
+                }
+                // In deferred mode, the object graph is traced when the work
+                // list is collected.
+                #[cfg(feature = "std")]
+                if collector::enqueue(self) {
+                    debug!("cactusref deferred cycle detection");
+                    return;
                 }
-                // otherwise, ignore the pointed to object; it will be dropped
-                // when there are no more remaining strong references to it.
+                collect_orphaned_cycle(self);
                 return;
             }
+            // If the object was never in a cycle, `dec_strong` above will kill
+            // the `Rc`.
+            //
+            // If the object was in a cycle, the `Rc` will only be dead if all
+            // strong references to it have been dropped.
             if self.inner().is_dead() {
-                drop_unreachable_with_adoptions(self);
-                return;
+                drop_unreachable(self);
             }
-            if let Some(cycle) = Self::orphaned_cycle(self) {
-                drop_cycle(cycle);
-                return;
-            }
-            debug!("cactusref drop skipped, Rc is reachable");
+            // otherwise, ignore the pointed to object; it will be dropped when
+            // there are no more remaining strong references to it.
         }
     }
 }
 
+// Deallocate the object graph `this` belongs to if it is an orphaned cycle.
+//
+// The strong reference of `this` must have been released.
+#[cfg(feature = "cycle-detection")]
+pub(crate) unsafe fn collect_orphaned_cycle<T>(this: &Rc<T>) {
+    if let Some(cycle) = Rc::orphaned_cycle(this) {
+        drop_cycle(cycle);
+    } else {
+        debug!("cactusref drop skipped, Rc is reachable");
+    }
+}
+
 unsafe fn drop_unreachable<T>(this: &mut Rc<T>) {
     debug!("cactusref detected unreachable Rc");
-    let forward = Link::forward(this.ptr);
-    let backward = Link::backward(this.ptr);
-    // Remove reverse links so `this` is not included in cycle detection for
-    // objects that had adopted `this`. This prevents a use-after-free in
-    // `Rc::orphaned_cycle`.
-    let links = this.inner().links();
-    for (item, &strong) in links.borrow().iter() {
-        match item.kind() {
-            Kind::Forward => {
-                let mut links = links.borrow_mut();
-                links.remove(forward, strong);
-                links.remove(backward, strong);
-            }
-            Kind::Loopback => {
-                let mut links = links.borrow_mut();
-                links.remove(*item, strong);
-            }
-            Kind::Backward => {}
-        }
-    }
+    // `Rc`s with links are dropped with `drop_unreachable_with_adoptions`, so
+    // `this` is not referenced by any other `Rc` in an object graph.
+    #[cfg(feature = "cycle-detection")]
+    debug_assert!(!this.inner().has_links());
 
     let rcbox = this.ptr.as_ptr();
     // Mark `this` as pending deallocation. This is not strictly necessary since
     // `this` is unreachable, but `kill`ing `this ensures we don't double-free.
-    if !(*rcbox).is_uninit() {
-        trace!("cactusref deallocating unreachable RcBox {:p}", rcbox);
+    if let RcState::Dead = (*rcbox).state() {
+        trace!("cactusref deallocating unreachable RcBox {rcbox:p}");
+        // Run the finalizer, if any, while `T` is still initialized.
+        #[cfg(feature = "cycle-detection")]
+        (*rcbox).finalize();
         // Mark the `RcBox` as uninitialized so we can make its `MaybeUninit`
         // fields uninhabited.
         (*rcbox).make_uninit();
@@ -194,11 +285,9 @@ unsafe fn drop_unreachable<T>(this: &mut Rc<T>) {
         let inner = mem::replace(&mut (*rcbox).value, MaybeUninit::uninit());
         // destroy the contained `T`.
         drop(inner.assume_init());
-        // Move the links `HashMap` out of the `RcBox`. Dropping an uninitialized
-        // `MaybeUninit` has no effect.
-        let links = mem::replace(&mut (*rcbox).links, MaybeUninit::uninit());
-        // Destroy the heap-allocated links.
-        drop(links.assume_init());
+        // Destroy the heap-allocated links, if any.
+        #[cfg(feature = "cycle-detection")]
+        drop((*rcbox).links.take());
     }
 
     // remove the implicit "strong weak" pointer now that we've destroyed the
@@ -213,71 +302,134 @@ unsafe fn drop_unreachable<T>(this: &mut Rc<T>) {
     }
 }
 
+#[cfg(feature = "cycle-detection")]
 unsafe fn drop_cycle<T>(cycle: HashMap<Link<T>, usize>) {
     debug!(
         "cactusref detected orphaned cycle with {} objects",
         cycle.len()
     );
+    #[cfg(debug_assertions)]
+    assert_balanced_adoptions(&cycle);
+
+    // The adoptions made by members are removed below, so read the sequence
+    // numbers which order the cycle for `DropOrder::ReverseAdoption` first.
+    #[cfg(feature = "std")]
+    let drop_order = drop_order();
+    #[cfg(feature = "std")]
+    let first_adoptions = if let DropOrder::ReverseAdoption = drop_order {
+        cycle
+            .keys()
+            .map(|ptr| {
+                let first_adoption = ptr
+                    .as_ref()
+                    .links()
+                    .map_or(0, |links| links.borrow().first_adoption());
+                (ptr.into_raw_non_null(), first_adoption)
+            })
+            .collect::<HashMap<_, _>>()
+    } else {
+        HashMap::default()
+    };
+
     // Iterate over all the nodes in the cycle, bust all of the links. All nodes
     // in the cycle are reachable by other nodes in the cycle, so removing
     // all cycle-internal links won't result in a leak.
     for (ptr, &refcount) in &cycle {
-        trace!(
-            "cactusref dropping {:?} member of orphaned cycle with refcount {}",
-            ptr,
-            refcount
-        );
+        trace!("cactusref dropping {ptr:?} member of orphaned cycle with refcount {refcount}");
 
         // Remove reverse links so `this` is not included in cycle detection for
         // objects that had adopted `this`. This prevents a use-after-free in
         // `Rc::orphaned_cycle`.
         //
-        // Because the entire cycle is unreachable, the only forward and
-        // backward links are to objects in the cycle that we are about to
-        // deallocate. This allows us to bust the cycle detection by clearing
-        // all links.
+        // Because the entire cycle is unreachable, every object which adopted
+        // a member of the cycle is in the cycle. Members may have adopted
+        // objects outside of the cycle which have other owners. Remove their
+        // back links to the member so they do not reference it once it is
+        // deallocated. Their strong counts are decremented when the member's
+        // `T` is dropped.
         let rcbox = ptr.as_ptr();
-        let cycle_strong_refs = {
-            let mut links = (*rcbox).links().borrow_mut();
-            links
-                .extract_if(|link, _| {
-                    if let Kind::Forward | Kind::Loopback = link.kind() {
-                        cycle.contains_key(link)
-                    } else {
-                        false
-                    }
-                })
-                .map(|(link, count)| {
-                    if let Kind::Forward = link.kind() {
-                        count
-                    } else {
-                        0
-                    }
-                })
-                .sum::<usize>()
-        };
+        if let Some(links) = (*rcbox).links() {
+            let survivors = links
+                .borrow_mut()
+                .extract_if(|link, _| matches!(link.kind(), Kind::Forward | Kind::Loopback))
+                .filter(|(link, _)| !cycle.contains_key(&link.as_forward()))
+                .collect::<vec::Vec<_>>();
+            for (survivor, adoptions) in survivors {
+                if let Some(links) = survivor.as_ref().links() {
+                    links
+                        .borrow_mut()
+                        .remove(Link::backward(ptr.into_raw_non_null()), adoptions);
+                }
+            }
+        }
 
         // To be in a cycle, at least one `value` field in an `RcBox` in the
-        // cycle holds a strong reference to `this`. Mark all nodes in the cycle
-        // as dead so when we deallocate them via the `value` pointer we don't
-        // get a double-free.
-        for _ in 0..cycle_strong_refs.min((*rcbox).strong()) {
+        // cycle holds a strong reference to `this`. `refcount` is the number of
+        // strong references to `this` held by members of the cycle. The cycle
+        // is orphaned, so these are all of the strong references to `this`.
+        //
+        // Mark all nodes in the cycle as dead before any `T` is dropped so
+        // `Weak`s to members of the cycle cannot be upgraded and report a
+        // strong count of zero while we deallocate them via the `value`
+        // pointer. This also prevents a double-free.
+        //
+        // The number of adoptions a member has made may differ from the
+        // number of adoptions of that member, so the strong count must be
+        // decremented by the incoming references.
+        for _ in 0..refcount.min((*rcbox).strong()) {
             (*rcbox).dec_strong();
         }
     }
 
-    let mut inners = vec![];
-    for (ptr, _) in &cycle {
-        if !ptr.is_dead() {
-            // This object continues to be referenced outside the cycle in
-            // another part of the graph.
-            continue;
+    // Filter the set of cycle participants so we only drop `Rc`s that are
+    // dead.
+    //
+    // If an `Rc` is not dead, it continues to be referenced outside of the
+    // cycle, for example:
+    //
+    //  | Rc | -> | Rc | -> | Rc | <-> | Rc |
+    //    ^                   |
+    //    |-------------------|
+    //
+    // This object continues to be referenced outside the cycle in another part
+    // of the graph.
+    #[cfg_attr(not(feature = "std"), allow(unused_mut))]
+    let mut unreachable_cycle_participants = cycle
+        .into_iter()
+        .map(|(ptr, _)| ptr)
+        .filter(RcInnerPtr::is_dead)
+        .map(Link::into_raw_non_null)
+        .collect::<vec::Vec<_>>();
+
+    // `T`s are dropped in the order of `unreachable_cycle_participants`, which
+    // follows the iteration order of the `cycle` hash map unless sorted.
+    #[cfg(feature = "std")]
+    match drop_order {
+        DropOrder::Unspecified => {}
+        DropOrder::Address => unreachable_cycle_participants.sort_unstable(),
+        DropOrder::ReverseAdoption => {
+            unreachable_cycle_participants.sort_unstable_by_key(|ptr| {
+                let first_adoption = first_adoptions.get(ptr).copied().unwrap_or_default();
+                (Reverse(first_adoption), *ptr)
+            });
         }
+    }
 
-        let ptr = ptr.into_raw_non_null();
+    // Run the finalizers of dead members before any `T` is moved out so every
+    // finalizer can observe the other members of the cycle. Finalizers are
+    // taken from the links before they are called, so each runs at most once.
+    for &ptr in &unreachable_cycle_participants {
         let rcbox = ptr.as_ptr();
+        if let RcState::Dead = (*rcbox).state() {
+            (*rcbox).finalize();
+        }
+    }
 
-        if !(*rcbox).is_uninit() {
+    let mut inners = vec![];
+    for &ptr in &unreachable_cycle_participants {
+        let rcbox = ptr.as_ptr();
+
+        if let RcState::Dead = (*rcbox).state() {
             // Mark the `RcBox` as uninitialized so we can make its
             // `MaybeUninit` fields uninhabited.
             (*rcbox).make_uninit();
@@ -285,55 +437,129 @@ unsafe fn drop_cycle<T>(cycle: HashMap<Link<T>, usize>) {
             // Move `T` out of the `RcBox`. Dropping an uninitialized
             // `MaybeUninit` has no effect.
             let inner = mem::replace(&mut (*rcbox).value, MaybeUninit::uninit());
-            // Move the links `HashMap` out of the `RcBox`. Dropping an
-            // uninitialized `MaybeUninit` has no effect.
-            let links = mem::replace(&mut (*rcbox).links, MaybeUninit::uninit());
-            trace!("cactusref deconstructed member {:p} of orphan cycle", rcbox);
-            // Move `T` and the `HashMap` out of the `RcBox` to be dropped after
+            // Move the heap-allocated links out of the `RcBox`.
+            let links = (*rcbox).links.take();
+            trace!("cactusref deconstructed member {rcbox:p} of orphan cycle");
+            // Move `T` and the links out of the `RcBox` to be dropped after
             // busting the cycle.
-            inners.push((inner.assume_init(), links.assume_init()));
+            inners.push((inner.assume_init(), links));
         }
     }
-    // Drop and deallocate all `T` and `HashMap` objects.
+
+    // Deallocate the `RcBox`es once all `T` and `HashMap` objects are dropped.
+    //
+    // The deallocation happens in the guard's `Drop` implementation so the
+    // cycle is released even if the `Drop` implementation of a `T` panics.
+    // Every member of the cycle is already dead and uninitialized, so no
+    // `RcBox` is dropped or deallocated twice while unwinding.
+    let _guard = DeallocateCycle {
+        participants: unreachable_cycle_participants,
+    };
+
+    // Drop all `T` and `HashMap` objects. If a `T` panics on drop, the
+    // remaining items in `inners` are still dropped during unwinding. A second
+    // panic aborts the process, matching the behavior of `std` collections.
     drop(inners);
+}
 
-    let unreachable_cycle_participants = cycle.into_iter().map(|(ptr, _)| ptr).filter(|ptr| {
-        // Filter the set of cycle participants so we only drop `Rc`s that are
-        // dead.
-        //
-        // If an `Rc` is not dead, it continues to be referenced outside of the
-        // cycle, for example:
-        //
-        //  | Rc | -> | Rc | -> | Rc | <-> | Rc |
-        //    ^                   |
-        //    |-------------------|
-        //
-        // This object continues to be referenced outside the cycle in another
-        // part of the graph.
-        ptr.is_dead()
-    });
+// Check that every member of an orphaned cycle has exactly as many strong
+// references as adoptions recorded by the other members of the cycle.
+//
+// Each adoption must be backed by a strong reference owned by the adopter and
+// released with `Adopt::unadopt` when the adopter drops it. An orphaned cycle
+// with more adoptions of a member than strong references means an `unadopt`
+// was forgotten or an adoption was recorded without a strong reference. The
+// panic message lists the adopters of each unbalanced member so the offending
+// `adopt_unchecked` and `unadopt` pairs can be found.
+//
+// The check panics before any links are torn down, so the cycle is leaked.
+#[cfg(all(debug_assertions, feature = "cycle-detection"))]
+fn assert_balanced_adoptions<T>(cycle: &HashMap<Link<T>, usize>) {
+    use alloc::string::String;
+    use core::fmt::Write;
 
-    for ptr in unreachable_cycle_participants {
-        let ptr = ptr.into_raw_non_null();
-        trace!(
-            "cactusref deallocating RcBox after dropping item {:?} in orphaned cycle",
-            ptr
+    // Do not turn an unwinding `Rc::drop` into an abort.
+    #[cfg(feature = "std")]
+    if std::thread::panicking() {
+        return;
+    }
+
+    let mut unbalanced = String::new();
+    for &node in cycle.keys() {
+        let Some(links) = node.as_ref().links() else {
+            continue;
+        };
+        let links = links.borrow();
+        // Adoptions of a member by itself are not counted. They do not keep
+        // any other member of the cycle alive.
+        let adopters = links
+            .iter()
+            .filter(|(link, _)| link.kind() == Kind::Backward)
+            .map(|(link, &count)| (link.as_forward(), count))
+            .filter(|(adopter, _)| *adopter != node && cycle.contains_key(adopter))
+            .collect::<vec::Vec<_>>();
+        let adoptions = adopters.iter().map(|&(_, count)| count).sum::<usize>();
+        let strong = node.strong();
+        if adoptions <= strong {
+            continue;
+        }
+        let to = value_addr(node);
+        let _ = write!(
+            unbalanced,
+            "\n  {to:#x} is adopted {adoptions} times but has {strong} strong references; adopted by"
         );
+        for (adopter, count) in adopters {
+            let from = value_addr(adopter);
+            error!("cactusref unbalanced adoption of {to:#x} by {from:#x} ({count} times)");
+            let _ = write!(unbalanced, " {from:#x} ({count} times)");
+        }
+    }
+    assert!(
+        unbalanced.is_empty(),
+        "cactusref orphaned cycle has unbalanced adoptions:{unbalanced}"
+    );
+}
 
-        let rcbox = ptr.as_ptr();
-        // remove the implicit "strong weak" pointer now that we've destroyed
-        // the contents.
-        (*rcbox).dec_weak();
-
-        if (*rcbox).weak() == 0 {
-            trace!(
-                "no more weak references, deallocating layout for item {:?} in orphaned cycle",
-                ptr
-            );
-            // SAFETY: `T` is `Sized`, which means `Layout::for_value_raw` is
-            // always safe to call.
-            let layout = Layout::for_value_raw(ptr.as_ptr());
-            Global.deallocate(ptr.cast(), layout);
+// Deallocates the `RcBox`es of dead members of an orphaned cycle.
+//
+// Members are deallocated when this guard is dropped, which happens on both
+// the normal return path and while unwinding from a panicking `T::drop` in
+// `drop_cycle`.
+#[cfg(feature = "cycle-detection")]
+struct DeallocateCycle<T> {
+    participants: vec::Vec<NonNull<RcBox<T>>>,
+}
+
+#[cfg(feature = "cycle-detection")]
+impl<T> Drop for DeallocateCycle<T> {
+    fn drop(&mut self) {
+        for ptr in self.participants.drain(..) {
+            trace!("cactusref deallocating RcBox after dropping item {ptr:?} in orphaned cycle");
+
+            let rcbox = ptr.as_ptr();
+            // SAFETY: `drop_cycle` only collects participants that are dead
+            // and whose contents have been moved out. The `RcBox` remains
+            // allocated until the implicit "strong weak" pointer is removed.
+            //
+            // Members of the cycle may hold `Weak`s to each other. Those
+            // `Weak`s are dropped with the `T`s before this guard runs, while
+            // every member still holds its implicit weak, so `Weak::drop`
+            // never deallocates a member of the cycle.
+            unsafe {
+                // remove the implicit "strong weak" pointer now that we've
+                // destroyed the contents.
+                (*rcbox).dec_weak();
+
+                if (*rcbox).weak() == 0 {
+                    trace!(
+                        "no more weak references, deallocating layout for item {ptr:?} in orphaned cycle"
+                    );
+                    // SAFETY: `T` is `Sized`, which means `Layout::for_value_raw`
+                    // is always safe to call.
+                    let layout = Layout::for_value_raw(ptr.as_ptr());
+                    Global.deallocate(ptr.cast(), layout);
+                }
+            }
         }
     }
 }
@@ -360,49 +586,22 @@ unsafe fn drop_cycle<T>(cycle: HashMap<Link<T>, usize>) {
 // |      |          |  |       |
 // |      |----------| <--------|
 // |--------------------|
+#[cfg(feature = "cycle-detection")]
 unsafe fn drop_unreachable_with_adoptions<T>(this: &mut Rc<T>) {
-    // Construct a forward and back link from `this` so we can
-    // purge it from the adopted `links`.
-    let forward = Link::forward(this.ptr);
-    let backward = Link::backward(this.ptr);
     // `this` is unreachable but may have been adopted and dropped.
     //
-    // Iterate over all of the other nodes in the graph that have links to
-    // `this` and remove all of the adoptions. By doing so, when other graph
-    // participants are dropped, they do not try to deallocate `this`.
-    //
     // `this` is fully removed from the graph.
-    let links = this.inner().links();
-    for (item, &strong) in links.borrow().iter() {
-        // if `this` has adopted itself, we don't need to clear these links in
-        // the loop to avoid an already borrowed error.
-        if ptr::eq(this.inner(), item.as_ptr()) {
-            continue;
-        }
-        let mut links = item.as_ref().links().borrow_mut();
-        // The cycle counts don't distinguish which nodes the cycle strong
-        // counts are from, so purge as many strong counts as possible.
-        //
-        // Additionally, `item` may have forward adoptions for `this`, so
-        // purge those as well.
-        //
-        // `Links::remove` ensures the count for forward and back links will not
-        // underflow.
-        links.remove(forward, strong);
-        links.remove(backward, strong);
-    }
-    // Bust the links for this since it is now unreachable and set to be
-    // deallocated.
-    links.borrow_mut().clear();
+    unlink(this);
 
     let rcbox = this.ptr.as_ptr();
     // Mark `this` as pending deallocation. This is not strictly necessary since
     // `this` is unreachable, but `kill`ing `this ensures we don't double-free.
-    if !(*rcbox).is_uninit() {
+    if let RcState::Dead = (*rcbox).state() {
         trace!(
-            "cactusref deallocating RcBox after dropping adopted and unreachable item {:p} in the object graph",
-            rcbox
+            "cactusref deallocating RcBox after dropping adopted and unreachable item {rcbox:p} in the object graph"
         );
+        // Run the finalizer, if any, while `T` is still initialized.
+        (*rcbox).finalize();
         // Mark the `RcBox` as uninitialized so we can make its `MaybeUninit`
         // fields uninhabited.
         (*rcbox).make_uninit();
@@ -412,11 +611,8 @@ unsafe fn drop_unreachable_with_adoptions<T>(this: &mut Rc<T>) {
         let inner = mem::replace(&mut (*rcbox).value, MaybeUninit::uninit());
         // destroy the contained `T`.
         drop(inner.assume_init());
-        // Move the links `HashMap` out of the `RcBox`. Dropping an uninitialized
-        // `MaybeUninit` has no effect.
-        let links = mem::replace(&mut (*rcbox).links, MaybeUninit::uninit());
         // Destroy the heap-allocated links.
-        drop(links.assume_init());
+        drop((*rcbox).links.take());
     }
 
     // remove the implicit "strong weak" pointer now that we've destroyed the
@@ -434,3 +630,42 @@ unsafe fn drop_unreachable_with_adoptions<T>(this: &mut Rc<T>) {
         Global.deallocate(this.ptr.cast(), layout);
     }
 }
+
+// Remove `this` from the object graph.
+//
+// Iterate over all of the other nodes in the graph that have links to `this`
+// and remove all of the adoptions. By doing so, when other graph participants
+// are dropped, they do not try to deallocate `this`.
+#[cfg(feature = "cycle-detection")]
+pub(crate) fn unlink<T>(this: &Rc<T>) {
+    let Some(links) = this.inner().links() else {
+        return;
+    };
+    // Construct a forward and back link from `this` so we can purge it from
+    // the adopted `links`.
+    let forward = Link::forward(this.ptr);
+    let backward = Link::backward(this.ptr);
+    for (item, &strong) in links.borrow().iter() {
+        // if `this` has adopted itself, we don't need to clear these links in
+        // the loop to avoid an already borrowed error.
+        if ptr::eq(this.inner(), item.as_ptr()) {
+            continue;
+        }
+        let Some(links) = item.as_ref().links() else {
+            continue;
+        };
+        let mut links = links.borrow_mut();
+        // The cycle counts don't distinguish which nodes the cycle strong
+        // counts are from, so purge as many strong counts as possible.
+        //
+        // Additionally, `item` may have forward adoptions for `this`, so
+        // purge those as well.
+        //
+        // `Links::remove` ensures the count for forward and back links will not
+        // underflow.
+        links.remove(forward, strong);
+        links.remove(backward, strong);
+    }
+    // Bust the links for this since it is now removed from the graph.
+    links.borrow_mut().clear();
+}
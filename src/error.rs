@@ -0,0 +1,173 @@
+use core::fmt;
+
+/// The reason [`Rc::try_unwrap_detailed`] failed to unwrap an [`Rc`].
+///
+/// [`Rc`]: crate::Rc
+/// [`Rc::try_unwrap_detailed`]: crate::Rc::try_unwrap_detailed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnwrapError {
+    /// The `Rc` has other strong references which are not owned by members of
+    /// a cycle it belongs to.
+    ///
+    /// The contained value is the strong count of the `Rc`.
+    SharedStrong(usize),
+    /// All other strong references to the `Rc` are owned by members of a cycle
+    /// it belongs to.
+    ///
+    /// The value can be reclaimed by dropping the `Rc`, which collects the
+    /// cycle if it has no other external owners.
+    CycleMember,
+}
+
+impl fmt::Display for UnwrapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SharedStrong(strong) => {
+                write!(f, "Rc has {strong} strong references and is shared")
+            }
+            Self::CycleMember => f.write_str("Rc is owned by members of a cycle"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnwrapError {}
+
+/// The reason [`Rc::try_make_mut`] refused to make a mutable reference into an
+/// [`Rc`].
+///
+/// [`Rc`]: crate::Rc
+/// [`Rc::try_make_mut`]: crate::Rc::try_make_mut
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MakeMutError {
+    /// All other strong references to the `Rc` are owned by members of a cycle
+    /// it belongs to.
+    ///
+    /// Cloning the inner value would detach the mutable copy from the object
+    /// graph, so mutations would not be visible to the other cycle members.
+    CycleMember,
+}
+
+impl fmt::Display for MakeMutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CycleMember => f.write_str("Rc is owned by members of a cycle"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MakeMutError {}
+
+/// The error returned by [`Rc::try_clone`] when the strong count of an [`Rc`]
+/// would overflow.
+///
+/// [`Rc`]: crate::Rc
+/// [`Rc::try_clone`]: crate::Rc::try_clone
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RefCountOverflow;
+
+impl fmt::Display for RefCountOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Rc strong count overflow")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RefCountOverflow {}
+
+/// The error returned by [`Rc::try_adopt`] when recording an adoption would
+/// grow an object graph beyond the limit set with [`set_max_graph_size`].
+///
+/// [`Rc::try_adopt`]: crate::Rc::try_adopt
+/// [`set_max_graph_size`]: crate::set_max_graph_size
+#[cfg(all(feature = "std", feature = "cycle-detection"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GraphTooLarge {
+    /// The maximum number of adoptions in an object graph at the time the
+    /// adoption was refused.
+    pub limit: usize,
+}
+
+#[cfg(all(feature = "std", feature = "cycle-detection"))]
+impl fmt::Display for GraphTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "object graph would exceed {} adoptions", self.limit)
+    }
+}
+
+#[cfg(all(feature = "std", feature = "cycle-detection"))]
+impl std::error::Error for GraphTooLarge {}
+
+/// A broken invariant of an object graph reported by [`Rc::validate_graph`].
+///
+/// Nodes are identified by the address of their value, which is the address
+/// returned by [`Rc::as_ptr`].
+///
+/// [`Rc::as_ptr`]: crate::Rc::as_ptr
+/// [`Rc::validate_graph`]: crate::Rc::validate_graph
+#[cfg(all(debug_assertions, feature = "cycle-detection"))]
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GraphInvariantViolation {
+    /// The adoptions `from` recorded of `to` do not match the back references
+    /// `to` recorded to `from`.
+    UnmatchedForwardLink {
+        /// The address of the adopting node.
+        from: usize,
+        /// The address of the adopted node.
+        to: usize,
+    },
+    /// The back references `to` recorded to `from` do not match the adoptions
+    /// `from` recorded of `to`.
+    UnmatchedBackwardLink {
+        /// The address of the adopting node.
+        from: usize,
+        /// The address of the adopted node.
+        to: usize,
+    },
+    /// A live node links to a node whose value has been dropped.
+    DeadLink {
+        /// The address of the live node.
+        from: usize,
+        /// The address of the dead node.
+        to: usize,
+    },
+    /// More adoptions of a node were recorded than it has strong references.
+    AdoptionsExceedStrongCount {
+        /// The address of the adopted node.
+        node: usize,
+        /// The number of recorded adoptions of the node.
+        adoptions: usize,
+        /// The strong count of the node.
+        strong: usize,
+    },
+}
+
+#[cfg(all(debug_assertions, feature = "cycle-detection"))]
+impl fmt::Display for GraphInvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::UnmatchedForwardLink { from, to } => write!(
+                f,
+                "adoption of {to:#x} by {from:#x} has no matching back reference"
+            ),
+            Self::UnmatchedBackwardLink { from, to } => write!(
+                f,
+                "back reference from {to:#x} to {from:#x} has no matching adoption"
+            ),
+            Self::DeadLink { from, to } => write!(f, "{from:#x} links to dead node {to:#x}"),
+            Self::AdoptionsExceedStrongCount {
+                node,
+                adoptions,
+                strong,
+            } => write!(
+                f,
+                "{node:#x} is adopted {adoptions} times but has {strong} strong references"
+            ),
+        }
+    }
+}
+
+#[cfg(all(debug_assertions, feature = "std", feature = "cycle-detection"))]
+impl std::error::Error for GraphInvariantViolation {}
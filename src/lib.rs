@@ -3,6 +3,7 @@
     core_intrinsics,
     dropck_eyepatch,
     layout_for_ptr,
+    negative_impls,
     set_ptr_value,
     slice_ptr_get
 )]
@@ -59,7 +60,11 @@
 //! proven to be safe. Although CactusRef makes a best effort to abort the
 //! program if it detects a dangling `Rc`, this crate may be unsound.
 //!
-//! [adopt-api]: crate::Adopt
+#![cfg_attr(feature = "cycle-detection", doc = "[adopt-api]: crate::Adopt")]
+#![cfg_attr(
+    not(feature = "cycle-detection"),
+    doc = "[adopt-api]: https://docs.rs/cactusref/latest/cactusref/trait.Adopt.html"
+)]
 //!
 //! # CactusRef vs. `std::rc`
 //!
@@ -120,20 +125,76 @@
 //! one empty hash map used to track adoptions and an if statement to check if
 //! these structures are empty on `drop`.
 //!
+//! These costs can be removed entirely by disabling the `cycle-detection`
+//! feature, which is enabled by default. Without this feature, [`Adopt`] is
+//! not available and `Rc` stores only its strong and weak counts alongside
+//! the value, like [`std::rc::Rc`].
+//!
 //! Cycle detection uses breadth-first search for traversing the object graph.
 //! The algorithm supports arbitrarily large object graphs and will not overflow
 //! the stack during the reachability trace.
 //!
+//! Latency-sensitive code can defer the deallocation of orphaned cycles to a
+//! safe point with the [`collector`] module, which requires the `std` feature.
+//!
+//! Code which builds object graphs from untrusted input can bound their size
+//! with [`set_max_graph_size`] and [`Rc::try_adopt`], which also require the
+//! `std` feature.
+//!
 //! [`std::rc::Rc`]: alloc::rc::Rc
 //! [`std::rc::Rc::downcast`]: alloc::rc::Rc::downcast
 //! [`CoerceUnsized`]: core::ops::CoerceUnsized
 //! [`DispatchFromDyn`]: core::ops::DispatchFromDyn
-
+#![cfg_attr(feature = "cycle-detection", doc = "[`Adopt`]: crate::Adopt")]
+#![cfg_attr(
+    not(feature = "cycle-detection"),
+    doc = "[`Adopt`]: https://docs.rs/cactusref/latest/cactusref/trait.Adopt.html"
+)]
+#![cfg_attr(
+    feature = "cycle-detection",
+    doc = "[`Adopt::adopt_unchecked`]: crate::Adopt::adopt_unchecked"
+)]
+#![cfg_attr(
+    not(feature = "cycle-detection"),
+    doc = "[`Adopt::adopt_unchecked`]: https://docs.rs/cactusref/latest/cactusref/trait.Adopt.html#tymethod.adopt_unchecked"
+)]
+#![cfg_attr(
+    feature = "cycle-detection",
+    doc = "[`Adopt::unadopt`]: crate::Adopt::unadopt"
+)]
+#![cfg_attr(
+    not(feature = "cycle-detection"),
+    doc = "[`Adopt::unadopt`]: https://docs.rs/cactusref/latest/cactusref/trait.Adopt.html#tymethod.unadopt"
+)]
+#![cfg_attr(
+    all(feature = "std", feature = "cycle-detection"),
+    doc = "[`collector`]: crate::collector"
+)]
+#![cfg_attr(
+    not(all(feature = "std", feature = "cycle-detection")),
+    doc = "[`collector`]: https://docs.rs/cactusref/latest/cactusref/collector/index.html"
+)]
+#![cfg_attr(
+    all(feature = "std", feature = "cycle-detection"),
+    doc = "[`set_max_graph_size`]: crate::set_max_graph_size"
+)]
+#![cfg_attr(
+    not(all(feature = "std", feature = "cycle-detection")),
+    doc = "[`set_max_graph_size`]: https://docs.rs/cactusref/latest/cactusref/fn.set_max_graph_size.html"
+)]
+#![cfg_attr(
+    all(feature = "std", feature = "cycle-detection"),
+    doc = "[`Rc::try_adopt`]: crate::Rc::try_adopt"
+)]
+#![cfg_attr(
+    not(all(feature = "std", feature = "cycle-detection")),
+    doc = "[`Rc::try_adopt`]: https://docs.rs/cactusref/latest/cactusref/struct.Rc.html#method.try_adopt"
+)]
 #![doc(html_root_url = "https://docs.rs/cactusref/0.5.0")]
 #![no_std]
 
 // Ensure code blocks in README.md compile
-#[cfg(doctest)]
+#[cfg(all(doctest, feature = "cycle-detection"))]
 #[doc = include_str!("../README.md")]
 mod readme {}
 
@@ -143,25 +204,76 @@ extern crate std;
 #[macro_use]
 extern crate log;
 
+#[cfg(feature = "cycle-detection")]
 mod adopt;
+#[cfg(feature = "cycle-detection")]
+pub mod collections;
+#[cfg(all(feature = "std", feature = "cycle-detection"))]
+pub mod collector;
+#[cfg(all(feature = "std", feature = "cycle-detection"))]
+mod config;
+#[cfg(feature = "cycle-detection")]
 mod cycle;
 mod drop;
+mod error;
+#[cfg(feature = "cycle-detection")]
 mod hash;
+#[cfg(feature = "cycle-detection")]
 mod link;
 mod rc;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod weak_ext;
 
 // Doc modules
-#[cfg(any(doctest, docsrs))]
+#[cfg(all(any(doctest, docsrs), feature = "cycle-detection"))]
 #[path = "doc/implementing_self_referential_data_structures.rs"]
 /// Examples of implementing self-referential data structures with CactusRef.
 pub mod implementing_self_referential_data_structures;
 
+#[cfg(feature = "cycle-detection")]
 pub use adopt::Adopt;
+#[cfg(all(feature = "std", feature = "cycle-detection"))]
+pub use config::{
+    drop_order, max_graph_size, set_drop_order, set_max_graph_size, set_trace_budget, trace_budget,
+    DropOrder,
+};
+#[cfg(all(debug_assertions, feature = "cycle-detection"))]
+pub use error::GraphInvariantViolation;
+#[cfg(all(feature = "std", feature = "cycle-detection"))]
+pub use error::GraphTooLarge;
+pub use error::{MakeMutError, RefCountOverflow, UnwrapError};
+pub use rc::Counts;
+pub use rc::PtrKey;
 pub use rc::Rc;
 pub use rc::Weak;
+pub use rc::WeakCounts;
+pub use rc::WeakRef;
+pub use weak_ext::retain_live;
 
 /// Cactus alias for [`Rc`].
 pub type CactusRef<T> = Rc<T>;
 
 /// Cactus alias for [`Weak`].
 pub type CactusWeakRef<T> = Weak<T>;
+
+// Expands to its input only if this crate is compiled with debug assertions.
+//
+// rustdoc compiles doctests with debug assertions even when this crate is
+// built without them, for example by `cargo test --release`, so
+// `#[cfg(debug_assertions)]` in a doctest cannot detect whether debug-only
+// APIs like `Rc::validate_graph` exist. Their examples are wrapped in this
+// macro instead.
+#[cfg(debug_assertions)]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __debug_assertions {
+    ($($tt:tt)*) => { $($tt)* };
+}
+
+#[cfg(not(debug_assertions))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __debug_assertions {
+    ($($tt:tt)*) => {};
+}
@@ -2,13 +2,16 @@
 
 //! This module includes data structures for building an object graph.
 
+use alloc::boxed::Box;
 use core::cell::Cell;
 use core::fmt;
-use core::hash::{Hash, Hasher};
+use core::hash::{BuildHasherDefault, Hash, Hasher};
+use core::mem::size_of;
 use core::num::NonZeroUsize;
 use core::ptr::{self, NonNull};
 
-use crate::hash::hash_map::{ExtractIf, Iter};
+#[cfg(feature = "std")]
+use crate::config::next_adoption_sequence;
 use crate::hash::HashMap;
 use crate::rc::{RcBox, RcInnerPtr};
 
@@ -19,16 +22,34 @@ pub(crate) enum Kind {
     Loopback,
 }
 
+/// A callback run with the value of an `Rc` just before the value is dropped.
+pub(crate) type Finalizer<T> = Box<dyn FnOnce(&T)>;
+
+/// The adoptions recorded for a link.
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    /// The number of adoptions of the link's pointee.
+    count: usize,
+    /// The sequence number of the adoption which created the link, or 0 if it
+    /// was not sequenced, see [`next_adoption_sequence`].
+    #[cfg(feature = "std")]
+    sequence: u64,
+}
+
 /// A collection of forward and backward links and their corresponding adoptions.
 pub(crate) struct Links<T> {
-    registry: HashMap<Link<T>, usize>,
+    registry: HashMap<Link<T>, Entry>,
+    /// A callback run with the value of the owning `Rc` just before the value
+    /// is dropped.
+    finalizer: Option<Finalizer<T>>,
 }
 
 impl<T> fmt::Debug for Links<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("Links")
-            .field("registry", &self.registry)
-            .finish()
+        let mut debug = f.debug_struct("Links");
+        debug.field("registry", &self.registry);
+        debug.field("has_finalizer", &self.finalizer.is_some());
+        debug.finish()
     }
 }
 
@@ -37,23 +58,111 @@ impl<T> Links<T> {
     pub fn new() -> Self {
         Self {
             registry: HashMap::default(),
+            finalizer: None,
+        }
+    }
+
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            registry: HashMap::with_capacity_and_hasher(capacity, BuildHasherDefault::default()),
+            finalizer: None,
         }
     }
 
+    /// Returns the sequence number of the oldest link to an `Rc` adopted by
+    /// the owning `Rc`, or 0 if none of its links were sequenced.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn first_adoption(&self) -> u64 {
+        self.registry
+            .values()
+            .map(|entry| entry.sequence)
+            .filter(|&sequence| sequence > 0)
+            .min()
+            .unwrap_or_default()
+    }
+
+    /// Replace the finalizer of the owning `Rc`, returning the previous one.
+    #[inline]
+    pub fn set_finalizer(&mut self, finalizer: Finalizer<T>) -> Option<Finalizer<T>> {
+        self.finalizer.replace(finalizer)
+    }
+
+    /// Remove the finalizer of the owning `Rc`, if any.
+    #[inline]
+    pub fn take_finalizer(&mut self) -> Option<Finalizer<T>> {
+        self.finalizer.take()
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.registry.capacity()
+    }
+
+    /// Reserve capacity for at least `additional` more links.
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.registry.reserve(additional);
+    }
+
+    /// Shrink the registry to fit the links it holds.
+    #[inline]
+    pub fn shrink_to_fit(&mut self) {
+        self.registry.shrink_to_fit();
+    }
+
+    /// Returns an estimate of the number of bytes allocated for the registry.
+    ///
+    /// This counts one entry and one control byte for each link the registry
+    /// can hold without reallocating, which underestimates the spare buckets
+    /// of the hash table.
+    #[inline]
+    pub fn heap_size(&self) -> usize {
+        self.registry.capacity() * (size_of::<(Link<T>, Entry)>() + 1)
+    }
+
+    /// Record an adoption of `other`.
+    ///
+    /// The first adoption of a forward or loopback link assigns it a sequence
+    /// number, which is cleared when the link is removed.
     #[inline]
     pub fn insert(&mut self, other: Link<T>) {
-        *self.registry.entry(other).or_insert(0) += 1;
+        let entry = self.registry.entry(other).or_insert_with(|| Entry {
+            count: 0,
+            // Backward links mirror a forward link of the adopting `Rc` and
+            // are not ordered.
+            #[cfg(feature = "std")]
+            sequence: match other.kind() {
+                Kind::Forward | Kind::Loopback => next_adoption_sequence(),
+                Kind::Backward => 0,
+            },
+        });
+        entry.count += 1;
+    }
+
+    /// Returns the number of adoptions of `other`, if any.
+    #[cfg(debug_assertions)]
+    #[inline]
+    pub fn get(&self, other: Link<T>) -> Option<usize> {
+        self.registry.get(&other).map(|entry| entry.count)
     }
 
+    /// Remove up to `strong` adoptions of `other`.
+    ///
+    /// Returns whether any adoptions of `other` were present.
     #[inline]
-    pub fn remove(&mut self, other: Link<T>, strong: usize) {
-        let count = self.registry.get(&other).copied().unwrap_or_default();
-        let remaining_strong_count = count.checked_sub(strong).and_then(NonZeroUsize::new);
+    pub fn remove(&mut self, other: Link<T>, strong: usize) -> bool {
+        let Some(entry) = self.registry.get_mut(&other) else {
+            return false;
+        };
+        let remaining_strong_count = entry.count.checked_sub(strong).and_then(NonZeroUsize::new);
         if let Some(remaining_strong_count) = remaining_strong_count {
-            self.registry.insert(other, remaining_strong_count.get());
+            entry.count = remaining_strong_count.get();
         } else {
             self.registry.remove(&other);
         }
+        true
     }
 
     #[inline]
@@ -67,16 +176,20 @@ impl<T> Links<T> {
     }
 
     #[inline]
-    pub fn iter(&self) -> Iter<'_, Link<T>, usize> {
-        self.registry.iter()
+    pub fn iter(&self) -> impl Iterator<Item = (&Link<T>, &usize)> + Clone {
+        self.registry
+            .iter()
+            .map(|(link, entry)| (link, &entry.count))
     }
 
     #[inline]
-    pub fn extract_if<F>(&mut self, f: F) -> ExtractIf<'_, Link<T>, usize, F>
+    pub fn extract_if<'a, F>(&'a mut self, mut f: F) -> impl Iterator<Item = (Link<T>, usize)> + 'a
     where
-        F: FnMut(&Link<T>, &mut usize) -> bool,
+        F: FnMut(&Link<T>, &mut usize) -> bool + 'a,
     {
-        self.registry.extract_if(f)
+        self.registry
+            .extract_if(move |link, entry| f(link, &mut entry.count))
+            .map(|(link, entry)| (link, entry.count))
     }
 }
 
@@ -241,7 +241,9 @@
 //! [fully qualified syntax]: https://doc.rust-lang.org/book/ch19-03-advanced-traits.html#fully-qualified-syntax-for-disambiguation-calling-methods-with-the-same-name
 
 use core::borrow;
-use core::cell::{Cell, RefCell};
+use core::cell::Cell;
+#[cfg(feature = "cycle-detection")]
+use core::cell::{OnceCell, RefCell};
 use core::cmp::Ordering;
 use core::fmt;
 use core::hash::{Hash, Hasher};
@@ -255,10 +257,20 @@ use core::ptr::{self, NonNull};
 use alloc::alloc::handle_alloc_error;
 use alloc::alloc::{AllocError, Allocator, Global, Layout};
 use alloc::boxed::Box;
-
+use alloc::vec::Vec;
+
+#[cfg(feature = "graph-algorithms")]
+use crate::cycle::graph_nodes;
+#[cfg(feature = "cycle-detection")]
+use crate::drop::unlink;
+use crate::error::{MakeMutError, RefCountOverflow, UnwrapError};
+#[cfg(feature = "graph-algorithms")]
+use crate::link::Link;
+#[cfg(feature = "cycle-detection")]
 use crate::link::Links;
 
 #[cfg(test)]
+#[allow(clippy::manual_assert_eq)]
 #[allow(clippy::redundant_clone)]
 #[allow(clippy::uninlined_format_args)]
 mod tests;
@@ -266,25 +278,74 @@ mod tests;
 // This is repr(C) to future-proof against possible field-reordering, which
 // would interfere with otherwise safe [into|from]_raw() of transmutable
 // inner types.
+//
+// The strong count doubles as a lifecycle marker for the allocation:
+//
+// - A strong count of zero means the `RcBox` is dead. All strong references
+//   have been released, but the value may not have been dropped yet.
+// - A strong count of `usize::MAX` means the `RcBox` is uninitialized. The
+//   `value` field has been moved out and must not be accessed.
+//
+// `Rc::counts` decodes these sentinels for callers.
 #[repr(C)]
 pub(crate) struct RcBox<T> {
     strong: Cell<usize>,
+    // The weak count starts at one. All strong pointers collectively own an
+    // implicit weak reference which is released after the value is dropped.
     weak: Cell<usize>,
-    pub links: MaybeUninit<RefCell<Links<T>>>,
+    // A mark for graph algorithms implemented outside of this crate. Cycle
+    // detection does not read it.
+    //
+    // The mark is stored before `links` so the fields preceding `value` have
+    // no trailing padding, which the layout of `RcBox<()>` used to allocate
+    // and locate the value assumes.
+    #[cfg(feature = "graph-algorithms")]
+    mark: Cell<u8>,
+    // Adoption links are allocated on the first adoption so `Rc`s which never
+    // join an object graph only pay for one pointer.
+    #[cfg(feature = "cycle-detection")]
+    pub links: OnceCell<Box<RefCell<Links<T>>>>,
     pub value: MaybeUninit<T>,
 }
 
+#[cfg(feature = "cycle-detection")]
 impl<T> RcBox<T> {
+    /// Returns the adoption links of this `RcBox` if it has been linked into
+    /// an object graph.
+    #[inline]
+    pub(crate) fn links(&self) -> Option<&RefCell<Links<T>>> {
+        self.links.get().map(|links| &**links)
+    }
+
+    /// Returns the adoption links of this `RcBox`, allocating them if this
+    /// `RcBox` has not yet been linked into an object graph.
+    #[inline]
+    pub(crate) fn links_or_init(&self) -> &RefCell<Links<T>> {
+        self.links
+            .get_or_init(|| Box::new(RefCell::new(Links::new())))
+    }
+
+    /// Returns whether this `RcBox` has any adoption links.
+    #[inline]
+    pub(crate) fn has_links(&self) -> bool {
+        self.links().is_some_and(|links| !links.borrow().is_empty())
+    }
+
+    /// Runs the finalizer set with [`Rc::set_finalizer`], if any.
+    ///
+    /// The finalizer is removed before it is called, so it runs at most once.
+    ///
     /// # Safety
     ///
-    /// Callers must ensure this `RcBox` is not dead.
+    /// `value` must be initialized.
     #[inline]
-    pub(crate) unsafe fn links(&self) -> &RefCell<Links<T>> {
-        let links = &self.links;
-        // SAFETY: because callers have ensured the `RcBox` is not dead, `links`
-        // has not yet been deallocated and the `MaybeUninit` is inhabited.
-        let pointer_to_links = links as *const MaybeUninit<RefCell<Links<T>>>;
-        &*(pointer_to_links.cast::<RefCell<Links<T>>>())
+    pub(crate) unsafe fn finalize(&self) {
+        let finalizer = self
+            .links()
+            .and_then(|links| links.borrow_mut().take_finalizer());
+        if let Some(finalizer) = finalizer {
+            finalizer(self.value.assume_init_ref());
+        }
     }
 }
 
@@ -296,13 +357,24 @@ impl<T> RcBox<T> {
 /// The inherent methods of `Rc` are all associated functions, which means
 /// that you have to call them as e.g., [`Rc::get_mut(&mut value)`][get_mut] instead of
 /// `value.get_mut()`. This avoids conflicts with methods of the inner type `T`.
+/// The exceptions are [`rc_strong_count`] and [`rc_weak_count`], whose `rc_`
+/// prefix makes conflicts unlikely.
 ///
 /// [get_mut]: Rc::get_mut
+/// [`rc_strong_count`]: Rc::rc_strong_count
+/// [`rc_weak_count`]: Rc::rc_weak_count
 pub struct Rc<T> {
     pub(crate) ptr: NonNull<RcBox<T>>,
     phantom: PhantomData<RcBox<T>>,
 }
 
+// `Rc` is already `!Send` and `!Sync` because it holds a `NonNull`, but the
+// reference counts, adoption links, and cycle collection all assume a single
+// thread. Opt out explicitly so a change to the fields of `Rc` or `RcBox`
+// cannot make `Rc` thread-safe by accident.
+impl<T> !Send for Rc<T> {}
+impl<T> !Sync for Rc<T> {}
+
 /// `Rc` is not `Send`.
 ///
 /// ```compile_fail
@@ -311,6 +383,15 @@ pub struct Rc<T> {
 /// let rc = Rc::<usize>::new(1);
 /// requires_send(rc);
 /// ```
+///
+/// Even if `T` is `Send`, an `Rc` cannot be moved to another thread:
+///
+/// ```compile_fail,E0277
+/// use std::thread;
+/// use cactusref::Rc;
+/// let rc = Rc::<usize>::new(1);
+/// thread::spawn(move || assert_eq!(*rc, 1));
+/// ```
 mod rc_is_not_send {}
 
 /// `Rc` is not `Sync`.
@@ -323,6 +404,85 @@ mod rc_is_not_send {}
 /// ```
 mod rc_is_not_sync {}
 
+/// `Rc` requires a `Sized` value. Unsized types like `[T]`, `str`, and trait
+/// objects fail to compile with an error pointing at the implicit `Sized`
+/// bound on the type parameter of `Rc`.
+///
+/// ```compile_fail,E0277
+/// use cactusref::Rc;
+/// fn slice(rc: Rc<[u8]>) {}
+/// ```
+///
+/// ```compile_fail,E0277
+/// use cactusref::Rc;
+/// fn string(rc: Rc<str>) {}
+/// ```
+///
+/// ```compile_fail,E0277
+/// use cactusref::Rc;
+/// fn trait_object(rc: Rc<dyn core::fmt::Debug>) {}
+/// ```
+///
+/// Store the unsized value behind a sized owner instead:
+///
+/// ```
+/// use cactusref::Rc;
+/// let slice = Rc::new(vec![1_u8, 2, 3]);
+/// let string = Rc::new(String::from("cactus"));
+/// let trait_object = Rc::new(Box::new(5) as Box<dyn core::fmt::Debug>);
+/// ```
+mod rc_is_not_unsized {}
+
+/// A snapshot of the reference counts and lifecycle state of an [`Rc`]
+/// allocation.
+///
+/// `Rc` encodes the lifecycle of its allocation in the strong count. A strong
+/// count of zero marks the allocation as dead and a strong count of
+/// `usize::MAX` marks the allocation as uninitialized, which means its value
+/// has been moved out to be dropped. `Counts` decodes these sentinels so the
+/// reported counts are never misleading.
+///
+/// The lifecycle state is only observable as something other than live from
+/// within a [`Drop`] implementation of a value in an object graph that is
+/// being collected.
+///
+/// `Counts` are returned by [`Rc::counts`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Counts {
+    /// The number of strong (`Rc`) pointers to this allocation.
+    ///
+    /// This is zero if the allocation is dead or uninitialized.
+    pub strong: usize,
+    /// The number of [`Weak`] pointers to this allocation.
+    pub weak: usize,
+    /// Whether all strong references to this allocation have been released.
+    ///
+    /// Uninitialized allocations are always dead.
+    pub dead: bool,
+    /// Whether the value in this allocation has been moved out to be dropped.
+    pub uninit: bool,
+}
+
+/// The weak count of an [`Rc`] allocation, split into the [`Weak`] pointers
+/// held by callers and the total number of weak references.
+///
+/// Every allocation holds one implicit weak reference on behalf of all of its
+/// strong pointers. The implicit weak keeps the allocation alive while the
+/// strong destructor is running and is released once the value is dropped.
+/// [`Rc::weak_count`] hides it; `total` includes it.
+///
+/// `WeakCounts` are returned by [`Rc::weak_count_detailed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WeakCounts {
+    /// The number of [`Weak`] pointers to this allocation.
+    ///
+    /// This is the same as [`Rc::weak_count`].
+    pub external: usize,
+    /// The number of weak references to this allocation, including the
+    /// implicit weak reference owned by the strong pointers.
+    pub total: usize,
+}
+
 impl<T> Rc<T> {
     #[inline(always)]
     pub(crate) fn inner(&self) -> &RcBox<T> {
@@ -331,7 +491,7 @@ impl<T> Rc<T> {
         unsafe { self.ptr.as_ref() }
     }
 
-    fn from_inner(ptr: NonNull<RcBox<T>>) -> Self {
+    pub(crate) fn from_inner(ptr: NonNull<RcBox<T>>) -> Self {
         Self {
             ptr,
             phantom: PhantomData,
@@ -362,7 +522,10 @@ impl<T> Rc<T> {
             Box::leak(Box::new(RcBox {
                 strong: Cell::new(1),
                 weak: Cell::new(1),
-                links: MaybeUninit::new(RefCell::new(Links::new())),
+                #[cfg(feature = "graph-algorithms")]
+                mark: Cell::new(0),
+                #[cfg(feature = "cycle-detection")]
+                links: OnceCell::new(),
                 value: MaybeUninit::new(value),
             }))
             .into(),
@@ -404,6 +567,280 @@ impl<T> Rc<T> {
         unsafe { Pin::new_unchecked(Rc::new(value)) }
     }
 
+    /// Constructs a new `Rc<T>` for each value yielded by `values`.
+    ///
+    /// This is a convenience for building the nodes of an object graph up
+    /// front. The returned `Vec` is sized once using the iterator's size hint.
+    ///
+    /// Each node is an independent allocation. The returned `Rc`s are not
+    /// adopted by each other and can be dropped or unwrapped individually.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::Rc;
+    ///
+    /// let nodes = Rc::new_many(0..3);
+    /// assert_eq!(nodes.len(), 3);
+    /// assert_eq!(*nodes[2], 2);
+    /// assert!(nodes.iter().all(|node| Rc::strong_count(node) == 1));
+    /// ```
+    #[must_use]
+    pub fn new_many<I>(values: I) -> Vec<Rc<T>>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let values = values.into_iter();
+        let mut nodes = Vec::with_capacity(values.size_hint().0);
+        nodes.extend(values.map(Rc::new));
+        nodes
+    }
+
+    /// Constructs a new `Rc<T>` with space for `edge_capacity` adoption links
+    /// preallocated.
+    ///
+    /// [`Rc::new`] allocates the adoption links of an `Rc` on its first
+    /// adoption and grows them as more distinct `Rc`s are adopted by or
+    /// adopt it. Preallocating the links avoids these reallocations.
+    ///
+    /// Every `Rc` stores its own links, and each distinct `Rc` it adopts or is
+    /// adopted by takes one entry. This only helps the `Rc`s constructed with
+    /// this function, so it is most useful for the seed of an object graph
+    /// that links to many other nodes, like the head of a list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::{Adopt, Rc};
+    ///
+    /// let head = Rc::new_graphed_with_capacity(0, 100);
+    /// let nodes = Rc::new_many(1..=100);
+    /// for node in &nodes {
+    ///     unsafe {
+    ///         Rc::adopt_unchecked(&head, node);
+    ///     }
+    /// }
+    /// assert_eq!(Rc::out_degree(&head), 100);
+    /// # for node in &nodes {
+    /// #     Rc::unadopt(&head, node);
+    /// # }
+    /// ```
+    #[cfg(feature = "cycle-detection")]
+    #[must_use]
+    pub fn new_graphed_with_capacity(value: T, edge_capacity: usize) -> Rc<T> {
+        let this = Rc::new(value);
+        let links = Box::new(RefCell::new(Links::with_capacity(edge_capacity)));
+        // A freshly allocated `Rc` has no links, so the cell is empty.
+        let _ = this.inner().links.set(links);
+        this
+    }
+
+    /// Reserves space for at least `additional` more adoption links in `this`.
+    ///
+    /// Like [`Rc::new_graphed_with_capacity`], but for `Rc`s which already
+    /// exist, such as nodes which join an object graph after they are
+    /// constructed. Reserving ahead of a burst of adoptions avoids growing the
+    /// adoption links of `this` several times.
+    ///
+    /// Every `Rc` stores its own links, and each distinct `Rc` it adopts or is
+    /// adopted by takes one entry. Only the links of `this` are reserved. The
+    /// adoption links are allocated if `this` has not been adopted, but `this`
+    /// is not graphed until its first adoption.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::{Adopt, Rc};
+    ///
+    /// let head = Rc::new(0);
+    /// let nodes = Rc::new_many(1..=100);
+    ///
+    /// Rc::reserve_adoptions(&head, nodes.len());
+    /// assert!(!Rc::is_graphed(&head));
+    /// for node in &nodes {
+    ///     unsafe {
+    ///         Rc::adopt_unchecked(&head, node);
+    ///     }
+    /// }
+    /// assert_eq!(Rc::out_degree(&head), 100);
+    /// # for node in &nodes {
+    /// #     Rc::unadopt(&head, node);
+    /// # }
+    /// ```
+    #[cfg(feature = "cycle-detection")]
+    pub fn reserve_adoptions(this: &Self, additional: usize) {
+        // The links of dead `Rc`s have already been torn down.
+        if this.inner().is_dead() {
+            return;
+        }
+        this.inner()
+            .links_or_init()
+            .borrow_mut()
+            .reserve(additional);
+    }
+
+    /// Constructs a new `Rc<T>` while giving you a `Weak<T>` to the allocation,
+    /// to allow you to construct a `T` which holds a weak pointer to itself,
+    /// returning an error if allocation fails.
+    ///
+    /// The allocation is made before `data_fn` is called. If allocation fails,
+    /// `data_fn` is not called and [`AllocError`] is returned.
+    ///
+    /// Calling [`upgrade`] on the weak reference inside `data_fn` returns
+    /// [`None`], because the value has not been constructed yet. Once
+    /// `try_new_cyclic` returns, weak references which were cloned and stored
+    /// in `T` can be upgraded.
+    ///
+    /// The weak reference passed to `data_fn` becomes the implicit weak
+    /// reference owned by the strong pointers, so it is not counted by
+    /// [`Rc::weak_count`]. Only clones of it which outlive `data_fn` are.
+    ///
+    /// If `data_fn` panics, the allocation is freed once all weak references
+    /// created from it have been dropped.
+    ///
+    /// [`upgrade`]: Weak::upgrade
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AllocError`] if the `Rc` could not be allocated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    ///
+    /// use cactusref::{Rc, Weak};
+    ///
+    /// struct Gadget {
+    ///     me: Weak<Gadget>,
+    /// }
+    ///
+    /// let gadget = Rc::try_new_cyclic(|me| {
+    ///     assert!(me.upgrade().is_none());
+    ///     Gadget { me: me.clone() }
+    /// })
+    /// .unwrap();
+    ///
+    /// let me = gadget.me.upgrade().unwrap();
+    /// assert!(Rc::ptr_eq(&gadget, &me));
+    /// ```
+    pub fn try_new_cyclic<F>(data_fn: F) -> Result<Rc<T>, AllocError>
+    where
+        F: FnOnce(&Weak<T>) -> T,
+    {
+        Rc::try_new_cyclic_with(data_fn, |layout| Global.allocate(layout))
+    }
+
+    /// Constructs a new `Pin<Rc<T>>` while giving you a `Weak<T>` to the
+    /// allocation, to allow you to construct a pinned `T` which holds a weak
+    /// pointer to itself.
+    ///
+    /// This combines [`Rc::try_new_cyclic`] with [`Rc::pin`]. The value is
+    /// constructed in place and never moved once `data_fn` returns, so it may
+    /// rely on its address for self-referential data structures like
+    /// intrusive lists.
+    ///
+    /// Weak references passed to or cloned from `data_fn` behave as described
+    /// in [`Rc::try_new_cyclic`]. Upgrading them yields an unpinned `Rc<T>`.
+    ///
+    /// # Safety
+    ///
+    /// Callers must ensure that the value is never moved out of its allocation
+    /// through an `Rc<T>` upgraded from one of these `Weak`s, for example with
+    /// [`Rc::try_unwrap`], [`Rc::take`], [`Rc::replace`],
+    /// [`Rc::try_into_box`], [`Rc::map_value`], or [`Rc::make_mut`]. This
+    /// includes `Rc`s upgraded after the returned `Pin<Rc<T>>` is dropped.
+    /// Moving the value violates the [pinning guarantees] and is *[undefined
+    /// behavior]* if `T` is not [`Unpin`].
+    ///
+    /// [pinning guarantees]: core::pin
+    /// [undefined behavior]: https://doc.rust-lang.org/reference/behavior-considered-undefined.html
+    ///
+    /// # Panics
+    ///
+    /// If the allocation fails, [`handle_alloc_error`] is called.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::marker::PhantomPinned;
+    ///
+    /// use cactusref::{Rc, Weak};
+    ///
+    /// struct Node {
+    ///     me: Weak<Node>,
+    ///     _pin: PhantomPinned,
+    /// }
+    ///
+    /// // SAFETY: `Rc`s upgraded from `me` are never used to move the value.
+    /// let node = unsafe {
+    ///     Rc::pin_cyclic(|me| Node {
+    ///         me: me.clone(),
+    ///         _pin: PhantomPinned,
+    ///     })
+    /// };
+    ///
+    /// let me = node.me.upgrade().unwrap();
+    /// assert!(std::ptr::eq(&*node, &*me));
+    /// ```
+    pub unsafe fn pin_cyclic<F>(data_fn: F) -> Pin<Rc<T>>
+    where
+        F: FnOnce(&Weak<T>) -> T,
+    {
+        let this = Rc::try_new_cyclic(data_fn)
+            .unwrap_or_else(|_| handle_alloc_error(Layout::new::<RcBox<T>>()));
+        // SAFETY: `Pin<Rc<T>>` never moves the value, and callers guarantee
+        // that `Rc`s upgraded from `Weak`s to the allocation never move it.
+        Pin::new_unchecked(this)
+    }
+
+    /// Constructs a new cyclic `Rc<T>` in memory returned by `allocate`.
+    ///
+    /// The memory must be compatible with [`Global`] because it is freed with
+    /// [`Global::deallocate`].
+    pub(crate) fn try_new_cyclic_with<F>(
+        data_fn: F,
+        allocate: impl FnOnce(Layout) -> Result<NonNull<[u8]>, AllocError>,
+    ) -> Result<Rc<T>, AllocError>
+    where
+        F: FnOnce(&Weak<T>) -> T,
+    {
+        // SAFETY: `T` is sized, so its layout describes the value.
+        let inner =
+            unsafe { Rc::try_allocate_for_layout(Layout::new::<T>(), allocate, <*mut u8>::cast)? };
+        // SAFETY: `try_allocate_for_layout` returns a non-null pointer to an
+        // `RcBox` with initialized counts.
+        let ptr = unsafe { NonNull::new_unchecked(inner) };
+
+        // Construct the `RcBox` with no strong references so the weak pointer
+        // cannot be upgraded until the value is written. The implicit weak
+        // reference is owned by `init_weak` until construction succeeds. If
+        // `data_fn` panics, dropping `init_weak` frees the allocation once no
+        // other weak references remain.
+        unsafe {
+            (*inner).strong.set(0);
+        }
+        let init_weak = Weak {
+            ptr,
+            phantom: PhantomData,
+        };
+
+        let data = data_fn(&init_weak);
+
+        unsafe {
+            // SAFETY: The value has not been initialized and there are no
+            // strong references that could observe it.
+            (*inner).value = MaybeUninit::new(data);
+            // `inc_strong` aborts when the strong count is 0, so set the count
+            // directly.
+            (*inner).strong.set(1);
+        }
+
+        // The implicit weak reference is now owned by the strong reference.
+        mem::forget(init_weak);
+        Ok(Rc::from_inner(ptr))
+    }
+
     /// Returns the inner value, if the `Rc` has exactly one strong reference.
     ///
     /// Otherwise, an [`Err`] is returned with the same `Rc` that was
@@ -434,6 +871,14 @@ impl<T> Rc<T> {
             unsafe {
                 let val = ptr::read(&*this); // copy the contained object
 
+                // Remove `this` from the object graph and destroy its
+                // heap-allocated links.
+                #[cfg(feature = "cycle-detection")]
+                {
+                    unlink(&this);
+                    drop((*this.ptr.as_ptr()).links.take());
+                }
+
                 // Indicate to Weaks that they can't be promoted by decrementing
                 // the strong count, and then remove the implicit "strong weak"
                 // pointer while also handling drop logic by just crafting a
@@ -450,6 +895,367 @@ impl<T> Rc<T> {
             Err(this)
         }
     }
+
+    /// Returns the inner value, if the `Rc` has exactly one strong reference.
+    ///
+    /// Otherwise, an [`Err`] is returned with the same `Rc` that was passed in
+    /// and an [`UnwrapError`] describing why the `Rc` could not be unwrapped.
+    ///
+    /// This will succeed even if there are outstanding weak references.
+    ///
+    /// Unlike [`Rc::try_unwrap`], this function distinguishes between an `Rc`
+    /// which is shared with other owners and an `Rc` whose other strong
+    /// references are all held by members of a cycle it has been adopted into.
+    /// Classifying the failure traverses the object graph and runs in
+    /// `O(links + nodes)` time if `this` has any adoptions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::{Rc, UnwrapError};
+    ///
+    /// let x = Rc::new(3);
+    /// assert_eq!(Rc::try_unwrap_detailed(x).ok(), Some(3));
+    ///
+    /// let x = Rc::new(4);
+    /// let _y = Rc::clone(&x);
+    /// let (_x, err) = Rc::try_unwrap_detailed(x).unwrap_err();
+    /// assert_eq!(err, UnwrapError::SharedStrong(2));
+    /// ```
+    ///
+    /// ```
+    /// # #[cfg(feature = "cycle-detection")]
+    /// # fn main() {
+    /// use cactusref::{Adopt, Rc, UnwrapError};
+    ///
+    /// let x = Rc::new(5);
+    /// let clone = Rc::clone(&x);
+    /// unsafe {
+    ///     Rc::adopt_unchecked(&x, &clone);
+    /// }
+    /// let (_x, err) = Rc::try_unwrap_detailed(x).unwrap_err();
+    /// assert_eq!(err, UnwrapError::CycleMember);
+    /// # drop(clone);
+    /// # }
+    /// # #[cfg(not(feature = "cycle-detection"))]
+    /// # fn main() {}
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the given `Rc` does not have exactly one strong reference, it is
+    /// returned in the `Err` variant of the returned `Result` along with the
+    /// reason the unwrap failed.
+    ///
+    /// If any strong references are held outside of cycles `this` belongs to,
+    /// [`UnwrapError::SharedStrong`] is returned. Otherwise,
+    /// [`UnwrapError::CycleMember`] is returned.
+    ///
+    /// If the `cycle-detection` feature is disabled, `Rc`s cannot be adopted
+    /// into cycles and [`UnwrapError::SharedStrong`] is always returned.
+    pub fn try_unwrap_detailed(this: Self) -> Result<T, (Self, UnwrapError)> {
+        match Rc::try_unwrap(this) {
+            Ok(value) => Ok(value),
+            #[cfg(feature = "cycle-detection")]
+            Err(this) if Rc::strong_count_excluding_cycle(&this) <= 1 => {
+                Err((this, UnwrapError::CycleMember))
+            }
+            Err(this) => {
+                let strong = Rc::strong_count(&this);
+                Err((this, UnwrapError::SharedStrong(strong)))
+            }
+        }
+    }
+
+    /// Moves the inner value into a [`Box`], if the `Rc` is uniquely owned.
+    ///
+    /// The value is only moved if [`get_mut`] would succeed, i.e. there are no
+    /// other `Rc` or [`Weak`] pointers to the allocation. Otherwise, an [`Err`]
+    /// is returned with the same `Rc` that was passed in.
+    ///
+    /// `Rc`s which have adopted or been adopted by other `Rc`s are not
+    /// converted either. A member of a cycle may hold the only strong
+    /// reference to another member, so a strong count of one does not mean
+    /// the value is exclusively owned.
+    ///
+    /// [`get_mut`]: Rc::get_mut
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::Rc;
+    ///
+    /// let x = Rc::new(3);
+    /// assert_eq!(Rc::try_into_box(x).as_deref(), Ok(&3));
+    ///
+    /// let x = Rc::new(4);
+    /// let _y = Rc::clone(&x);
+    /// assert!(Rc::try_into_box(x).is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the given `Rc` is not unique or is part of an object graph, it is
+    /// returned in the `Err` variant of the returned `Result`.
+    pub fn try_into_box(this: Self) -> Result<Box<T>, Self> {
+        #[cfg(feature = "cycle-detection")]
+        if Rc::is_graphed(&this) {
+            return Err(this);
+        }
+        if !Rc::is_unique(&this) {
+            return Err(this);
+        }
+        match Rc::try_unwrap(this) {
+            Ok(value) => Ok(Box::new(value)),
+            Err(_) => unreachable!("unique Rc has exactly one strong reference"),
+        }
+    }
+
+    /// Transforms the inner value with `f`, if the `Rc` is uniquely owned.
+    ///
+    /// The value is only transformed if [`get_mut`] would succeed, i.e. there
+    /// are no other `Rc` or [`Weak`] pointers to the allocation, and the `Rc`
+    /// has not adopted or been adopted by other `Rc`s. Otherwise, an [`Err`]
+    /// is returned with the same `Rc` that was passed in and `f` is not called.
+    ///
+    /// If the allocation of an `Rc<U>` has the same layout as the allocation
+    /// of `this`, the allocation is reused. Otherwise, the value returned by
+    /// `f` is moved into a new allocation.
+    ///
+    /// Any finalizer set with [`Rc::set_finalizer`] is dropped without being
+    /// called. If `f` panics, the allocation is freed.
+    ///
+    /// [`get_mut`]: Rc::get_mut
+    #[cfg_attr(
+        feature = "cycle-detection",
+        doc = "[`Rc::set_finalizer`]: Rc::set_finalizer"
+    )]
+    #[cfg_attr(
+        not(feature = "cycle-detection"),
+        doc = "[`Rc::set_finalizer`]: https://docs.rs/cactusref/latest/cactusref/struct.Rc.html#method.set_finalizer"
+    )]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::Rc;
+    ///
+    /// let x = Rc::new(3);
+    /// let x = Rc::map_value(x, |x| x.to_string()).unwrap();
+    /// assert_eq!(*x, "3");
+    ///
+    /// let x = Rc::new(4);
+    /// let _y = Rc::clone(&x);
+    /// let x = Rc::map_value(x, |x| x.to_string()).unwrap_err();
+    /// assert_eq!(*x, 4);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the given `Rc` is not unique or is part of an object graph, it is
+    /// returned in the `Err` variant of the returned `Result`.
+    pub fn map_value<U, F>(this: Self, f: F) -> Result<Rc<U>, Self>
+    where
+        F: FnOnce(T) -> U,
+    {
+        #[cfg(feature = "cycle-detection")]
+        if Rc::is_graphed(&this) {
+            return Err(this);
+        }
+        if !Rc::is_unique(&this) {
+            return Err(this);
+        }
+        let layout = Layout::new::<RcBox<T>>();
+        if layout != Layout::new::<RcBox<U>>() {
+            return match Rc::try_unwrap(this) {
+                Ok(value) => Ok(Rc::new(f(value))),
+                Err(_) => unreachable!("unique Rc has exactly one strong reference"),
+            };
+        }
+
+        let ptr = this.ptr;
+        mem::forget(this);
+        unsafe {
+            // SAFETY: `this` was unique, so there are no other `Rc` or `Weak`
+            // pointers which could observe the allocation while it is reused.
+            let value = ptr::read((*ptr.as_ptr()).value.as_ptr());
+            // Destroy the heap-allocated links, which are empty but may hold a
+            // finalizer.
+            #[cfg(feature = "cycle-detection")]
+            drop((*ptr.as_ptr()).links.take());
+
+            // Free the allocation if `f` panics.
+            let guard = DeallocateOnUnwind {
+                ptr: ptr.cast(),
+                layout,
+            };
+            let value = f(value);
+            mem::forget(guard);
+
+            let ptr = ptr.cast::<RcBox<U>>();
+            ptr::write(
+                ptr.as_ptr(),
+                RcBox {
+                    strong: Cell::new(1),
+                    weak: Cell::new(1),
+                    #[cfg(feature = "graph-algorithms")]
+                    mark: Cell::new(0),
+                    #[cfg(feature = "cycle-detection")]
+                    links: OnceCell::new(),
+                    value: MaybeUninit::new(value),
+                },
+            );
+            Ok(Rc::from_inner(ptr))
+        }
+    }
+
+    /// Sets a finalizer which is called with the value of this allocation just
+    /// before the value is dropped, replacing any previously set finalizer.
+    ///
+    /// The finalizer runs when the last strong reference to the allocation is
+    /// dropped, including when the allocation is a member of an orphaned cycle
+    /// which is being collected. Finalizers allow releasing resources tied to
+    /// a specific node without implementing [`Drop`] for `T`.
+    ///
+    /// When an orphaned cycle is collected, the finalizers of all members run
+    /// before any of their values are dropped, so finalizers may observe other
+    /// members of the cycle.
+    ///
+    /// The finalizer is dropped without being called if the value is moved
+    /// out of the allocation, for example with [`Rc::try_unwrap`] or
+    /// [`Rc::make_mut`], or if the `Rc` is leaked. If a finalizer panics while
+    /// an orphaned cycle is being collected, the cycle is leaked.
+    ///
+    /// The finalizer is stored alongside the adoption links of the allocation,
+    /// which are allocated if `this` has not been adopted.
+    ///
+    /// # Aborts
+    ///
+    /// Finalizers run after the strong counts of the allocation, and of every
+    /// other member of a collected cycle, have dropped to zero. A finalizer
+    /// may read through the `&T` it is given, but it must not clone any `Rc`
+    /// which points into the allocation or the cycle being collected. Doing so
+    /// aborts the process. Upgrading a [`Weak`] to such an allocation returns
+    /// [`None`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::cell::Cell;
+    /// use cactusref::Rc;
+    ///
+    /// let finalized = std::rc::Rc::new(Cell::new(0));
+    /// let node = Rc::new(10);
+    /// let clone = Rc::clone(&node);
+    ///
+    /// let counter = std::rc::Rc::clone(&finalized);
+    /// Rc::set_finalizer(&node, Box::new(move |value: &i32| counter.set(*value)));
+    ///
+    /// drop(node);
+    /// assert_eq!(finalized.get(), 0);
+    /// drop(clone);
+    /// assert_eq!(finalized.get(), 10);
+    /// ```
+    #[cfg(feature = "cycle-detection")]
+    pub fn set_finalizer(this: &Self, f: Box<dyn FnOnce(&T)>) {
+        // Drop the previous finalizer after releasing the borrow in case it
+        // owns `Rc`s in the object graph.
+        let previous = this.inner().links_or_init().borrow_mut().set_finalizer(f);
+        drop(previous);
+    }
+}
+
+// Deallocates an `RcBox` whose contents have been moved out.
+//
+// `Rc::map_value` forgets the guard once the allocation is reused, so the
+// allocation is only freed while unwinding from a panic in the mapping
+// function.
+struct DeallocateOnUnwind {
+    ptr: NonNull<u8>,
+    layout: Layout,
+}
+
+impl Drop for DeallocateOnUnwind {
+    fn drop(&mut self) {
+        // SAFETY: The allocation was made by `Global` with `layout` and its
+        // contents have been moved out.
+        unsafe {
+            Global.deallocate(self.ptr, self.layout);
+        }
+    }
+}
+
+#[cfg(feature = "graph-algorithms")]
+impl<T> Rc<T> {
+    /// Returns the mark of this allocation.
+    ///
+    /// Every allocation has a mark byte which is initialized to 0 and is only
+    /// modified by [`Rc::set_mark`] and [`Rc::clear_marks_in_graph`]. Marks
+    /// can be used to implement graph algorithms over object graphs, like
+    /// tri-color marking, without tracking per-node state in a side table.
+    ///
+    /// Marks are shared by all `Rc`s which point to the same allocation. They
+    /// are not used by cycle detection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::Rc;
+    ///
+    /// let node = Rc::new(());
+    /// let clone = Rc::clone(&node);
+    /// assert_eq!(Rc::mark(&node), 0);
+    ///
+    /// Rc::set_mark(&node, 2);
+    /// assert_eq!(Rc::mark(&clone), 2);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn mark(this: &Self) -> u8 {
+        this.inner().mark.get()
+    }
+
+    /// Sets the mark of this allocation.
+    ///
+    /// See [`Rc::mark`].
+    #[inline]
+    pub fn set_mark(this: &Self, mark: u8) {
+        this.inner().mark.set(mark);
+    }
+
+    /// Resets the mark of every member of the object graph `this` belongs to,
+    /// including `this`, to 0.
+    ///
+    /// The object graph includes every `Rc` reachable from `this` by following
+    /// adoptions in either direction, like [`Rc::graph_size`].
+    ///
+    /// This function traverses the object graph and runs in `O(links + nodes)`
+    /// time if `this` has any adoptions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::{Adopt, Rc};
+    ///
+    /// let parent = Rc::new(());
+    /// let child = Rc::new(());
+    /// let clone = Rc::clone(&child);
+    /// unsafe {
+    ///     Rc::adopt_unchecked(&parent, &clone);
+    /// }
+    /// Rc::set_mark(&parent, 1);
+    /// Rc::set_mark(&child, 1);
+    ///
+    /// Rc::clear_marks_in_graph(&child);
+    /// assert_eq!(Rc::mark(&parent), 0);
+    /// assert_eq!(Rc::mark(&child), 0);
+    /// # Rc::unadopt(&parent, &clone);
+    /// ```
+    pub fn clear_marks_in_graph(this: &Self) {
+        for node in graph_nodes(Link::forward(this.ptr)) {
+            node.as_ref().mark.set(0);
+        }
+    }
 }
 
 impl<T> Rc<MaybeUninit<T>> {
@@ -508,97 +1314,389 @@ impl<T> Rc<T> {
     /// let _ = unsafe { Rc::from_raw(x_ptr) };
     /// ```
     #[must_use]
-    pub fn into_raw(this: Self) -> *const T {
-        let ptr = Self::as_ptr(&this);
-        mem::forget(this);
-        ptr
+    pub fn into_raw(this: Self) -> *const T {
+        let ptr = Self::as_ptr(&this);
+        mem::forget(this);
+        ptr
+    }
+
+    /// Consumes and leaks the `Rc`, returning a reference to the wrapped value,
+    /// `&'a T`.
+    ///
+    /// The type `T` must outlive the chosen lifetime `'a`. If the type has only
+    /// static references, or none at all, then this may be chosen to be
+    /// `'static`.
+    ///
+    /// This function is mainly useful for data that lives for the remainder of
+    /// the program's life. The strong reference held by `this` is never
+    /// released, so the value is never dropped and its allocation is never
+    /// freed.
+    ///
+    /// **This leaks the allocation and every allocation reachable from it.**
+    /// A leaked `Rc` is a permanent external owner of any cycle it is a member
+    /// of, which means no member of that cycle is ever collected.
+    ///
+    /// The leaked `Rc` may be reclaimed by converting the returned reference
+    /// to a pointer and passing it to [`Rc::from_raw`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::Rc;
+    ///
+    /// let x = Rc::new(41);
+    /// let static_ref: &'static i32 = Rc::leak(x);
+    /// assert_eq!(*static_ref, 41);
+    /// # // Reconstruct the `Rc` to avoid a leak.
+    /// # drop(unsafe { Rc::from_raw(static_ref) });
+    /// ```
+    #[must_use]
+    pub fn leak<'a>(this: Self) -> &'a T
+    where
+        T: 'a,
+    {
+        let ptr = Rc::into_raw(this);
+        // SAFETY: `into_raw` forgets `this` without releasing its strong
+        // reference, so the value is never dropped or deallocated.
+        unsafe { &*ptr }
+    }
+
+    /// Provides a raw pointer to the data.
+    ///
+    /// The counts are not affected in any way and the `Rc` is not consumed. The pointer is valid
+    /// for as long there are strong counts in the `Rc`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::Rc;
+    ///
+    /// let x = Rc::new("hello".to_owned());
+    /// let y = Rc::clone(&x);
+    /// let x_ptr = Rc::as_ptr(&x);
+    /// assert_eq!(x_ptr, Rc::as_ptr(&y));
+    /// assert_eq!(unsafe { &*x_ptr }, "hello");
+    /// ```
+    #[must_use]
+    pub fn as_ptr(this: &Self) -> *const T {
+        let ptr: *mut RcBox<T> = NonNull::as_ptr(this.ptr);
+
+        // SAFETY: This cannot go through Deref::deref or Rc::inner because
+        // this is required to retain raw/mut provenance such that e.g. `get_mut` can
+        // write through the pointer after the Rc is recovered through `from_raw`.
+        unsafe {
+            // SAFETY: we can cast the `MaybeUninit<T>` to a `T` because we are
+            // calling and associated function with a live `Rc`. If an `Rc` is
+            // not dead, the inner `MaybeUninit` is inhabited.
+            ptr::addr_of_mut!((*ptr).value).cast::<T>()
+        }
+    }
+
+    /// Constructs an `Rc<T>` from a raw pointer.
+    ///
+    /// The raw pointer must have been previously returned by a call to
+    /// [`Rc<U>::into_raw`][into_raw] where `U` must have the same size
+    /// and alignment as `T`. This is trivially true if `U` is `T`.
+    /// Note that if `U` is not `T` but has the same size and alignment, this is
+    /// basically like transmuting references of different types. See
+    /// [`mem::transmute`][transmute] for more information on what
+    /// restrictions apply in this case.
+    ///
+    /// The user of `from_raw` has to make sure a specific value of `T` is only
+    /// dropped once.
+    ///
+    /// This function is unsafe because improper use may lead to memory unsafety,
+    /// even if the returned `Rc<T>` is never accessed.
+    ///
+    /// [into_raw]: Rc::into_raw
+    /// [transmute]: core::mem::transmute
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::Rc;
+    ///
+    /// let x = Rc::new("hello".to_owned());
+    /// let x_ptr = Rc::into_raw(x);
+    ///
+    /// unsafe {
+    ///     // Convert back to an `Rc` to prevent leak.
+    ///     let x = Rc::from_raw(x_ptr);
+    ///     assert_eq!(&*x, "hello");
+    ///
+    ///     // Further calls to `Rc::from_raw(x_ptr)` would be memory-unsafe.
+    /// }
+    ///
+    /// // The memory was freed when `x` went out of scope above, so `x_ptr` is now dangling!
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// Callers must ensure that `ptr` points to a live `Rc` and was created
+    /// with a call to [`Rc::into_raw`].
+    pub unsafe fn from_raw(ptr: *const T) -> Self {
+        let offset = data_offset(ptr);
+
+        // Reverse the offset to find the original RcBox.
+        let rc_ptr = (ptr as *mut u8)
+            .offset(-offset)
+            .with_metadata_of(ptr as *mut RcBox<T>);
+
+        Self::from_ptr(rc_ptr)
+    }
+
+    /// Constructs an `Rc<T>` from a raw pointer and overwrites the reference
+    /// counts of its allocation.
+    ///
+    /// This is intended for FFI code which tracks the ownership of an `Rc`
+    /// allocation outside of the `Rc` after converting it with
+    /// [`Rc::into_raw`]. Once the foreign code is done, its view of the strong
+    /// and weak counts is written back to the allocation.
+    ///
+    /// `strong` is the number of `Rc`s that will point to the allocation,
+    /// including the returned `Rc`, and `weak` is the number of [`Weak`]s, as
+    /// reported by [`Rc::strong_count`] and [`Rc::weak_count`].
+    ///
+    /// Prefer [`Rc::from_raw`] with [`Rc::increment_strong_count`] and
+    /// [`Rc::decrement_strong_count`], which cannot corrupt the counts.
+    ///
+    /// # Safety
+    ///
+    /// Callers must ensure that `ptr` was created with a call to
+    /// [`Rc::into_raw`] and that the allocation has not been freed and its
+    /// value has not been dropped.
+    ///
+    /// `strong` must be at least 1 and must equal the number of `Rc`s which
+    /// will own the allocation, counting the returned `Rc` and every `Rc` or
+    /// raw pointer that will be converted back with [`Rc::from_raw`].
+    /// Adoptions recorded with [`Adopt`] are not modified, so `strong` must
+    /// include the `Rc`s owned by adopting values.
+    ///
+    /// `weak` must be less than [`usize::MAX`] and must equal the number of
+    /// `Weak`s which will point to the allocation.
+    ///
+    /// Counts which are too low cause a use-after-free once the excess
+    /// pointers are dropped. Counts which are too high leak the allocation.
+    ///
+    #[cfg_attr(feature = "cycle-detection", doc = "[`Adopt`]: crate::Adopt")]
+    #[cfg_attr(
+        not(feature = "cycle-detection"),
+        doc = "[`Adopt`]: https://docs.rs/cactusref/latest/cactusref/trait.Adopt.html"
+    )]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::Rc;
+    ///
+    /// let x = Rc::new(String::from("hello"));
+    /// let ptr = Rc::into_raw(x);
+    ///
+    /// // Foreign code takes and releases ownership of the value...
+    ///
+    /// unsafe {
+    ///     let x = Rc::from_raw_parts(ptr, 1, 0);
+    ///     assert_eq!(Rc::strong_count(&x), 1);
+    ///     assert_eq!(*x, "hello");
+    /// }
+    /// ```
+    pub unsafe fn from_raw_parts(ptr: *const T, strong: usize, weak: usize) -> Self {
+        debug_assert!(strong > 0, "Rc::from_raw_parts requires a strong count");
+        let rc = Rc::from_raw(ptr);
+        let inner = rc.inner();
+        inner.strong.set(strong);
+        // Add the implicit weak reference owned by the strong pointers.
+        inner.weak.set(weak + 1);
+        rc
+    }
+
+    /// Creates a new [`Weak`] pointer to this allocation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::Rc;
+    ///
+    /// let five = Rc::new(5);
+    ///
+    /// let weak_five = Rc::downgrade(&five);
+    /// ```
+    #[must_use]
+    pub fn downgrade(this: &Self) -> Weak<T> {
+        this.inner().inc_weak();
+        // Make sure we do not create a dangling Weak
+        debug_assert!(!is_dangling(this.ptr.as_ptr()));
+        Weak {
+            ptr: this.ptr,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Creates a new [`Weak`] pointer to this allocation, intended to be
+    /// stored in the allocation's own value.
+    ///
+    /// This is equivalent to [`Rc::downgrade`]. Use it to add weak
+    /// self-references after construction, for example to a collection of
+    /// `Weak`s, where [`Rc::try_new_cyclic`] can only provide one up front.
+    ///
+    /// Weak self-references do not keep the allocation alive, so they do not
+    /// need to be adopted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::cell::RefCell;
+    /// use cactusref::{Rc, Weak};
+    ///
+    /// struct Node {
+    ///     this: RefCell<Option<Weak<Node>>>,
+    /// }
+    ///
+    /// let node = Rc::new(Node { this: RefCell::new(None) });
+    /// *node.this.borrow_mut() = Some(Rc::self_weak(&node));
+    ///
+    /// assert_eq!(Rc::weak_count(&node), 1);
+    /// let this = node.this.borrow().as_ref().and_then(Weak::upgrade);
+    /// assert!(Rc::ptr_eq(&this.unwrap(), &node));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn self_weak(this: &Self) -> Weak<T> {
+        Rc::downgrade(this)
+    }
+
+    /// Borrows a [`WeakRef`] to this allocation.
+    ///
+    /// Unlike [`Rc::downgrade`], this does not increment the weak count.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::Rc;
+    ///
+    /// let five = Rc::new(5);
+    /// let weak_five = Rc::weak_ref(&five);
+    ///
+    /// assert_eq!(Rc::weak_count(&five), 0);
+    /// assert_eq!(weak_five.upgrade().as_deref(), Some(&5));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn weak_ref(this: &Self) -> WeakRef<'_, T> {
+        WeakRef {
+            ptr: this.ptr,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Gets the number of [`Weak`] pointers to this allocation.
+    ///
+    /// This is the number of `Weak`s created with [`Rc::downgrade`] or cloned
+    /// from them which have not been dropped. The implicit weak reference the
+    /// allocation holds on behalf of its strong pointers is not counted. Use
+    /// [`Rc::weak_count_detailed`] to include it, see [`WeakCounts`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::Rc;
+    ///
+    /// let five = Rc::new(5);
+    /// assert_eq!(0, Rc::weak_count(&five));
+    ///
+    /// let _weak_five = Rc::downgrade(&five);
+    ///
+    /// assert_eq!(1, Rc::weak_count(&five));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn weak_count(this: &Self) -> usize {
+        this.inner().weak() - 1
+    }
+
+    /// Gets the number of weak references to this allocation, distinguishing
+    /// [`Weak`] pointers from the implicit weak reference owned by the strong
+    /// pointers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::{Rc, WeakCounts};
+    ///
+    /// let five = Rc::new(5);
+    /// assert_eq!(
+    ///     Rc::weak_count_detailed(&five),
+    ///     WeakCounts {
+    ///         external: 0,
+    ///         total: 1,
+    ///     }
+    /// );
+    ///
+    /// let _weak_five = Rc::downgrade(&five);
+    /// assert_eq!(
+    ///     Rc::weak_count_detailed(&five),
+    ///     WeakCounts {
+    ///         external: 1,
+    ///         total: 2,
+    ///     }
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn weak_count_detailed(this: &Self) -> WeakCounts {
+        let total = this.inner().weak();
+        WeakCounts {
+            // Subtract the implicit "strong weak" pointer.
+            external: total - 1,
+            total,
+        }
     }
 
-    /// Provides a raw pointer to the data.
-    ///
-    /// The counts are not affected in any way and the `Rc` is not consumed. The pointer is valid
-    /// for as long there are strong counts in the `Rc`.
+    /// Gets the number of strong (`Rc`) pointers to this allocation.
     ///
     /// # Examples
     ///
     /// ```
     /// use cactusref::Rc;
     ///
-    /// let x = Rc::new("hello".to_owned());
-    /// let y = Rc::clone(&x);
-    /// let x_ptr = Rc::as_ptr(&x);
-    /// assert_eq!(x_ptr, Rc::as_ptr(&y));
-    /// assert_eq!(unsafe { &*x_ptr }, "hello");
+    /// let five = Rc::new(5);
+    /// let _also_five = Rc::clone(&five);
+    ///
+    /// assert_eq!(2, Rc::strong_count(&five));
     /// ```
+    #[inline]
     #[must_use]
-    pub fn as_ptr(this: &Self) -> *const T {
-        let ptr: *mut RcBox<T> = NonNull::as_ptr(this.ptr);
-
-        // SAFETY: This cannot go through Deref::deref or Rc::inner because
-        // this is required to retain raw/mut provenance such that e.g. `get_mut` can
-        // write through the pointer after the Rc is recovered through `from_raw`.
-        unsafe {
-            // SAFETY: we can cast the `MaybeUninit<T>` to a `T` because we are
-            // calling and associated function with a live `Rc`. If an `Rc` is
-            // not dead, the inner `MaybeUninit` is inhabited.
-            ptr::addr_of_mut!((*ptr).value).cast::<T>()
-        }
+    pub fn strong_count(this: &Self) -> usize {
+        this.inner().strong()
     }
 
-    /// Constructs an `Rc<T>` from a raw pointer.
-    ///
-    /// The raw pointer must have been previously returned by a call to
-    /// [`Rc<U>::into_raw`][into_raw] where `U` must have the same size
-    /// and alignment as `T`. This is trivially true if `U` is `T`.
-    /// Note that if `U` is not `T` but has the same size and alignment, this is
-    /// basically like transmuting references of different types. See
-    /// [`mem::transmute`][transmute] for more information on what
-    /// restrictions apply in this case.
-    ///
-    /// The user of `from_raw` has to make sure a specific value of `T` is only
-    /// dropped once.
-    ///
-    /// This function is unsafe because improper use may lead to memory unsafety,
-    /// even if the returned `Rc<T>` is never accessed.
+    /// Gets the number of strong (`Rc`) pointers to this allocation.
     ///
-    /// [into_raw]: Rc::into_raw
-    /// [transmute]: core::mem::transmute
+    /// This is a method form of [`Rc::strong_count`] for use in method
+    /// chains. The `rc_` prefix avoids shadowing methods on `T` which are
+    /// reachable through `Deref`.
     ///
     /// # Examples
     ///
     /// ```
     /// use cactusref::Rc;
     ///
-    /// let x = Rc::new("hello".to_owned());
-    /// let x_ptr = Rc::into_raw(x);
-    ///
-    /// unsafe {
-    ///     // Convert back to an `Rc` to prevent leak.
-    ///     let x = Rc::from_raw(x_ptr);
-    ///     assert_eq!(&*x, "hello");
-    ///
-    ///     // Further calls to `Rc::from_raw(x_ptr)` would be memory-unsafe.
-    /// }
+    /// let five = Rc::new(5);
+    /// let _also_five = Rc::clone(&five);
     ///
-    /// // The memory was freed when `x` went out of scope above, so `x_ptr` is now dangling!
+    /// assert_eq!(2, five.rc_strong_count());
     /// ```
-    ///
-    /// # Safety
-    ///
-    /// Callers must ensure that `ptr` points to a live `Rc` and was created
-    /// with a call to [`Rc::into_raw`].
-    pub unsafe fn from_raw(ptr: *const T) -> Self {
-        let offset = data_offset(ptr);
-
-        // Reverse the offset to find the original RcBox.
-        let rc_ptr = (ptr as *mut u8)
-            .offset(-offset)
-            .with_metadata_of(ptr as *mut RcBox<T>);
-
-        Self::from_ptr(rc_ptr)
+    #[inline]
+    #[must_use]
+    pub fn rc_strong_count(&self) -> usize {
+        Rc::strong_count(self)
     }
 
-    /// Creates a new [`Weak`] pointer to this allocation.
+    /// Gets the number of [`Weak`] pointers to this allocation.
+    ///
+    /// This is a method form of [`Rc::weak_count`] for use in method chains.
+    /// The `rc_` prefix avoids shadowing methods on `T` which are reachable
+    /// through `Deref`.
     ///
     /// # Examples
     ///
@@ -606,39 +1704,73 @@ impl<T> Rc<T> {
     /// use cactusref::Rc;
     ///
     /// let five = Rc::new(5);
+    /// let _weak_five = Rc::downgrade(&five);
     ///
-    /// let weak_five = Rc::downgrade(&five);
+    /// assert_eq!(1, five.rc_weak_count());
     /// ```
+    #[inline]
     #[must_use]
-    pub fn downgrade(this: &Self) -> Weak<T> {
-        this.inner().inc_weak();
-        // Make sure we do not create a dangling Weak
-        debug_assert!(!is_dangling(this.ptr.as_ptr()));
-        Weak {
-            ptr: this.ptr,
-            phantom: PhantomData,
-        }
+    pub fn rc_weak_count(&self) -> usize {
+        Rc::weak_count(self)
     }
 
-    /// Gets the number of [`Weak`] pointers to this allocation.
+    /// Gets a snapshot of the reference counts and lifecycle state of this
+    /// allocation.
+    ///
+    /// Unlike [`Rc::strong_count`], the returned [`Counts`] decode the
+    /// sentinel values `Rc` uses to track whether an allocation is dead or
+    /// uninitialized while an object graph is being collected.
     ///
     /// # Examples
     ///
     /// ```
-    /// use cactusref::Rc;
+    /// use cactusref::{Counts, Rc};
     ///
     /// let five = Rc::new(5);
+    /// let _also_five = Rc::clone(&five);
     /// let _weak_five = Rc::downgrade(&five);
     ///
-    /// assert_eq!(1, Rc::weak_count(&five));
+    /// assert_eq!(
+    ///     Rc::counts(&five),
+    ///     Counts {
+    ///         strong: 2,
+    ///         weak: 1,
+    ///         dead: false,
+    ///         uninit: false,
+    ///     }
+    /// );
     /// ```
     #[inline]
     #[must_use]
-    pub fn weak_count(this: &Self) -> usize {
-        this.inner().weak() - 1
+    pub fn counts(this: &Self) -> Counts {
+        let inner = this.inner();
+        let strong = match inner.state() {
+            RcState::Live(strong) => strong,
+            RcState::Dead | RcState::Uninit => 0,
+        };
+        Counts {
+            strong,
+            // Subtract the implicit "strong weak" pointer, which is held until
+            // the allocation is deallocated.
+            weak: inner.weak().saturating_sub(1),
+            dead: inner.is_dead(),
+            uninit: inner.is_uninit(),
+        }
     }
 
-    /// Gets the number of strong (`Rc`) pointers to this allocation.
+    /// Returns a wrapper whose [`Debug`] implementation shows the position of
+    /// this `Rc` in the object graph instead of its value.
+    ///
+    /// The output includes the address of this allocation, its strong and weak
+    /// counts, and the addresses of the allocations it has adopted (forward
+    /// links) and been adopted by (backward links). Addresses are formatted
+    /// the same way as the [`Pointer`] implementation of `Rc`.
+    ///
+    /// Only the links of this `Rc` are printed. The object graph is not
+    /// traversed, so formatting a member of a cycle terminates.
+    ///
+    /// [`Debug`]: fmt::Debug
+    /// [`Pointer`]: fmt::Pointer
     ///
     /// # Examples
     ///
@@ -646,14 +1778,13 @@ impl<T> Rc<T> {
     /// use cactusref::Rc;
     ///
     /// let five = Rc::new(5);
-    /// let _also_five = Rc::clone(&five);
-    ///
-    /// assert_eq!(2, Rc::strong_count(&five));
+    /// let debug = format!("{:?}", Rc::debug_graph(&five));
+    /// assert!(debug.contains(&format!("{five:p}")));
+    /// assert!(debug.contains("strong: 1"));
     /// ```
-    #[inline]
     #[must_use]
-    pub fn strong_count(this: &Self) -> usize {
-        this.inner().strong()
+    pub fn debug_graph(this: &Self) -> impl fmt::Debug + '_ {
+        DebugGraph { rc: this }
     }
 
     /// Increments the strong reference count on the `Rc<T>` associated with the
@@ -805,6 +1936,223 @@ impl<T> Rc<T> {
         &mut *(pointer_to_value)
     }
 
+    /// Returns a mutable reference to the possibly uninitialized value slot of
+    /// the given `Rc`, without any check.
+    ///
+    /// This allows dropping the value in place and writing a new one without
+    /// reallocating, for example to recycle allocations in an object pool.
+    ///
+    /// See also [`get_mut`], which is safe and does appropriate checks, and
+    /// [`Rc::replace`], which replaces the value of a unique `Rc`.
+    ///
+    /// [`get_mut`]: Rc::get_mut
+    ///
+    /// # Safety
+    ///
+    /// Any other `Rc` or [`Weak`] pointers to the same allocation must not be
+    /// dereferenced for the duration of the returned borrow.
+    ///
+    /// The slot is initialized when this function is called. If the value is
+    /// dropped or moved out through the returned reference, a new value must be
+    /// written before the borrow ends. Every other access to the `Rc`,
+    /// including dropping it, assumes the value is initialized.
+    ///
+    /// Adoptions are recorded on the allocation, not the value. If the old
+    /// value owns `Rc`s that this `Rc` has adopted, [`unadopt`] them before
+    /// the value is dropped.
+    ///
+    #[cfg_attr(
+        feature = "cycle-detection",
+        doc = "[`unadopt`]: crate::Adopt::unadopt"
+    )]
+    #[cfg_attr(
+        not(feature = "cycle-detection"),
+        doc = "[`unadopt`]: https://docs.rs/cactusref/latest/cactusref/trait.Adopt.html#tymethod.unadopt"
+    )]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::Rc;
+    ///
+    /// let mut x = Rc::new(String::from("foo"));
+    /// unsafe {
+    ///     let slot = Rc::as_uninit_mut(&mut x);
+    ///     slot.assume_init_drop();
+    ///     slot.write(String::from("bar"));
+    /// }
+    /// assert_eq!(*x, "bar");
+    /// ```
+    #[inline]
+    pub unsafe fn as_uninit_mut(this: &mut Self) -> &mut MaybeUninit<T> {
+        debug_assert!(!this.inner().is_dead());
+        // We are careful to *not* create a reference covering the "count"
+        // fields, as this would conflict with accesses to the reference counts
+        // (e.g. by `Weak`).
+        &mut (*this.ptr.as_ptr()).value
+    }
+
+    /// Exchanges the values of two uniquely owned `Rc`s, returning whether the
+    /// values were swapped.
+    ///
+    /// The values are only swapped if [`get_mut`] would succeed for both
+    /// `Rc`s, i.e. there are no other `Rc` or [`Weak`] pointers to either
+    /// allocation. Otherwise, neither `Rc` is modified and `false` is returned,
+    /// because swapping a shared value would be observable through the other
+    /// pointers.
+    ///
+    /// Values of `Rc`s which have adopted other `Rc`s are not swapped either.
+    /// Adoptions are recorded on the allocation, not the value, and would no
+    /// longer match the `Rc`s owned by the swapped values.
+    ///
+    /// This is an associated function that needs to be used as
+    /// `Rc::try_swap(...)`. A method would interfere with methods of the same
+    /// name on the contents of a `Rc` used through `Deref`.
+    ///
+    /// [`get_mut`]: Rc::get_mut
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::Rc;
+    ///
+    /// let mut a = Rc::new(1);
+    /// let mut b = Rc::new(2);
+    /// assert!(Rc::try_swap(&mut a, &mut b));
+    /// assert_eq!(*a, 2);
+    /// assert_eq!(*b, 1);
+    ///
+    /// let _shared = Rc::clone(&b);
+    /// assert!(!Rc::try_swap(&mut a, &mut b));
+    /// assert_eq!(*a, 2);
+    /// assert_eq!(*b, 1);
+    /// ```
+    #[allow(clippy::must_use_candidate)]
+    pub fn try_swap(this: &mut Self, other: &mut Self) -> bool {
+        #[cfg(feature = "cycle-detection")]
+        if Rc::is_graphed(this) || Rc::is_graphed(other) {
+            return false;
+        }
+        if !Rc::is_unique(this) || !Rc::is_unique(other) {
+            return false;
+        }
+        // SAFETY: Both `Rc`s are unique, so they point to distinct allocations
+        // and no other pointers can observe the values.
+        unsafe {
+            mem::swap(Rc::get_mut_unchecked(this), Rc::get_mut_unchecked(other));
+        }
+        true
+    }
+
+    /// Replaces the value of a uniquely owned `Rc`, returning the old value.
+    ///
+    /// The value is only replaced if [`get_mut`] would succeed, i.e. there are
+    /// no other `Rc` or [`Weak`] pointers to the allocation. Otherwise, `value`
+    /// is dropped, the `Rc` is not modified, and `None` is returned.
+    ///
+    /// This allows mutating exclusively owned values without wrapping them in
+    /// a [`RefCell`].
+    ///
+    /// Adoptions are recorded on the allocation, not the value. If the old
+    /// value owns `Rc`s that this `Rc` has adopted, [`unadopt`] them first.
+    ///
+    /// This is an associated function that needs to be used as
+    /// `Rc::replace(...)`. A method would interfere with methods of the same
+    /// name on the contents of a `Rc` used through `Deref`.
+    ///
+    /// [`get_mut`]: Rc::get_mut
+    /// [`RefCell`]: core::cell::RefCell
+    #[cfg_attr(
+        feature = "cycle-detection",
+        doc = "[`unadopt`]: crate::Adopt::unadopt"
+    )]
+    #[cfg_attr(
+        not(feature = "cycle-detection"),
+        doc = "[`unadopt`]: https://docs.rs/cactusref/latest/cactusref/trait.Adopt.html#tymethod.unadopt"
+    )]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::Rc;
+    ///
+    /// let mut x = Rc::new(3);
+    /// assert_eq!(Rc::replace(&mut x, 4), Some(3));
+    /// assert_eq!(*x, 4);
+    ///
+    /// let _y = Rc::clone(&x);
+    /// assert_eq!(Rc::replace(&mut x, 5), None);
+    /// assert_eq!(*x, 4);
+    /// ```
+    #[inline]
+    #[allow(clippy::must_use_candidate)]
+    pub fn replace(this: &mut Self, value: T) -> Option<T> {
+        Rc::get_mut(this).map(|slot| mem::replace(slot, value))
+    }
+
+    /// Takes the value of a uniquely owned `Rc`, leaving [`Default::default`]
+    /// in its place.
+    ///
+    /// Like [`Rc::replace`], the value is only taken if [`get_mut`] would
+    /// succeed. Otherwise, the `Rc` is not modified and `None` is returned.
+    ///
+    /// [`get_mut`]: Rc::get_mut
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::Rc;
+    ///
+    /// let mut x = Rc::new(String::from("foo"));
+    /// assert_eq!(Rc::take(&mut x).as_deref(), Some("foo"));
+    /// assert_eq!(*x, "");
+    ///
+    /// let _y = Rc::clone(&x);
+    /// assert_eq!(Rc::take(&mut x), None);
+    /// ```
+    #[inline]
+    #[allow(clippy::must_use_candidate)]
+    pub fn take(this: &mut Self) -> Option<T>
+    where
+        T: Default,
+    {
+        Rc::get_mut(this).map(mem::take)
+    }
+
+    /// Makes a clone of the `Rc` pointer, returning an error instead of
+    /// aborting if the strong count would overflow.
+    ///
+    /// [`Rc::clone`] aborts the process if the strong count overflows, like
+    /// [`std::rc::Rc`]. `try_clone` checks the strong count before
+    /// incrementing it so callers can recover, which may matter on 32-bit
+    /// targets with very large object graphs.
+    ///
+    /// [`std::rc::Rc`]: alloc::rc::Rc
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::Rc;
+    ///
+    /// let five = Rc::new(5);
+    /// let also_five = Rc::try_clone(&five).unwrap();
+    /// assert!(Rc::ptr_eq(&five, &also_five));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If incrementing the strong count would overflow, [`RefCountOverflow`]
+    /// is returned and the strong count is not modified.
+    #[inline]
+    pub fn try_clone(this: &Self) -> Result<Rc<T>, RefCountOverflow> {
+        // `usize::MAX` is used to mark the `Rc` as uninitialized, so the
+        // largest valid strong count is `usize::MAX - 1`.
+        if this.inner().strong() >= usize::MAX - 1 {
+            return Err(RefCountOverflow);
+        }
+        Ok(Rc::clone(this))
+    }
+
     /// Returns `true` if the two `Rc`s point to the same allocation
     /// (in a vein similar to [`ptr::eq`]).
     ///
@@ -827,6 +2175,58 @@ impl<T> Rc<T> {
     pub fn ptr_eq(this: &Self, other: &Self) -> bool {
         this.ptr.as_ptr() == other.ptr.as_ptr()
     }
+
+    /// Feeds the address of the allocation the `Rc` points to into the given
+    /// [`Hasher`].
+    ///
+    /// This hashes by identity and is consistent with [`Rc::ptr_eq`]. `Rc`'s
+    /// [`Hash`] implementation hashes the inner value instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::hash_map::DefaultHasher;
+    /// use std::hash::Hasher;
+    ///
+    /// use cactusref::Rc;
+    ///
+    /// let hash = |rc: &Rc<i32>| {
+    ///     let mut hasher = DefaultHasher::new();
+    ///     Rc::ptr_hash(rc, &mut hasher);
+    ///     hasher.finish()
+    /// };
+    ///
+    /// let five = Rc::new(5);
+    /// let same_five = Rc::clone(&five);
+    /// assert_eq!(hash(&five), hash(&same_five));
+    /// ```
+    #[inline]
+    pub fn ptr_hash<H: Hasher>(this: &Self, state: &mut H) {
+        this.ptr.hash(state);
+    }
+
+    /// Compares the addresses of the allocations two `Rc`s point to.
+    ///
+    /// This is a total order which is consistent with [`Rc::ptr_eq`] and is
+    /// stable for as long as the `Rc`s are alive. It can be used to iterate
+    /// over the nodes of an object graph in a deterministic order within one
+    /// process. The order of allocations is not guaranteed to be the same
+    /// across runs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::Rc;
+    ///
+    /// let mut nodes = Rc::new_many(0..10);
+    /// nodes.sort_by(Rc::ptr_cmp);
+    /// assert!(nodes.windows(2).all(|pair| Rc::as_ptr(&pair[0]) < Rc::as_ptr(&pair[1])));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn ptr_cmp(this: &Self, other: &Self) -> Ordering {
+        this.ptr.as_ptr().cmp(&other.ptr.as_ptr())
+    }
 }
 
 impl<T: Clone> Rc<T> {
@@ -841,8 +2241,14 @@ impl<T: Clone> Rc<T> {
     ///
     /// See also [`get_mut`], which will fail rather than cloning.
     ///
+    /// If the other `Rc` pointers are all owned by members of a cycle `this`
+    /// has been adopted into, the clone is not part of the object graph and
+    /// mutations are not visible to the rest of the cycle. See
+    /// [`try_make_mut`], which refuses to clone cycle members.
+    ///
     /// [`clone`]: Clone::clone
     /// [`get_mut`]: Rc::get_mut
+    /// [`try_make_mut`]: Rc::try_make_mut
     ///
     /// # Examples
     ///
@@ -896,6 +2302,14 @@ impl<T: Clone> Rc<T> {
                 let data: &mut MaybeUninit<T> = mem::transmute(Rc::get_mut_unchecked(&mut rc));
                 data.as_mut_ptr().copy_from_nonoverlapping(&**this, 1);
 
+                // Remove `this` from the object graph and destroy its
+                // heap-allocated links.
+                #[cfg(feature = "cycle-detection")]
+                {
+                    unlink(this);
+                    drop((*this.ptr.as_ptr()).links.take());
+                }
+
                 this.inner().dec_strong();
                 // Remove implicit strong-weak ref (no need to craft a fake
                 // Weak here -- we know other Weaks can clean up for us)
@@ -917,6 +2331,68 @@ impl<T: Clone> Rc<T> {
             &mut *(pointer_to_value)
         }
     }
+
+    /// Makes a mutable reference into the given `Rc`, refusing to clone the
+    /// inner value of a cycle member.
+    ///
+    /// This behaves like [`make_mut`], except when all other `Rc` pointers to
+    /// the same allocation are owned by members of a cycle `this` has been
+    /// adopted into. In that case, `make_mut` would clone the inner value into
+    /// a new allocation which has no adoptions, silently detaching `this` from
+    /// the object graph. `try_make_mut` returns an error instead.
+    ///
+    /// Classifying the shared owners traverses the object graph and runs in
+    /// `O(links + nodes)` time if `this` is shared and has any adoptions.
+    ///
+    /// [`make_mut`]: Rc::make_mut
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::Rc;
+    ///
+    /// let mut data = Rc::new(5);
+    /// let other_data = Rc::clone(&data);
+    ///
+    /// *Rc::try_make_mut(&mut data).unwrap() += 1; // Clones inner data
+    ///
+    /// assert_eq!(*data, 6);
+    /// assert_eq!(*other_data, 5);
+    /// ```
+    ///
+    /// ```
+    /// # #[cfg(feature = "cycle-detection")]
+    /// # fn main() {
+    /// use cactusref::{Adopt, MakeMutError, Rc};
+    ///
+    /// let mut data = Rc::new(5);
+    /// let clone = Rc::clone(&data);
+    /// unsafe {
+    ///     Rc::adopt_unchecked(&data, &clone);
+    /// }
+    /// assert_eq!(Rc::try_make_mut(&mut data), Err(MakeMutError::CycleMember));
+    /// # drop(clone);
+    /// # }
+    /// # #[cfg(not(feature = "cycle-detection"))]
+    /// # fn main() {}
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If all strong references to `this` other than `this` itself are held by
+    /// members of cycles `this` belongs to, [`MakeMutError::CycleMember`] is
+    /// returned and the inner value is not cloned.
+    ///
+    /// If the `cycle-detection` feature is disabled, `Rc`s cannot be adopted
+    /// into cycles and this function never returns an error.
+    #[inline]
+    pub fn try_make_mut(this: &mut Self) -> Result<&mut T, MakeMutError> {
+        #[cfg(feature = "cycle-detection")]
+        if Rc::strong_count(this) != 1 && Rc::strong_count_excluding_cycle(this) <= 1 {
+            return Err(MakeMutError::CycleMember);
+        }
+        Ok(Rc::make_mut(this))
+    }
 }
 
 impl<T> Rc<T> {
@@ -974,10 +2450,10 @@ impl<T> Rc<T> {
 
         ptr::write(&mut (*inner).strong, Cell::new(1));
         ptr::write(&mut (*inner).weak, Cell::new(1));
-        ptr::write(
-            &mut (*inner).links,
-            MaybeUninit::new(RefCell::new(Links::new())),
-        );
+        #[cfg(feature = "graph-algorithms")]
+        ptr::write(&mut (*inner).mark, Cell::new(0));
+        #[cfg(feature = "cycle-detection")]
+        ptr::write(&mut (*inner).links, OnceCell::new());
 
         Ok(inner)
     }
@@ -1000,7 +2476,7 @@ impl<T> Rc<T> {
             let box_unique = NonNull::new_unchecked(box_unique);
             let box_ptr = box_unique.as_ptr();
 
-            let value_size = mem::size_of_val(&*box_ptr);
+            let value_size = size_of_val(&*box_ptr);
             let ptr = Self::allocate_for_ptr(box_ptr);
 
             // Copy value as bytes
@@ -1040,6 +2516,13 @@ impl<T> Clone for Rc<T> {
     /// This creates another pointer to the same allocation, increasing the
     /// strong reference count.
     ///
+    /// Cloning an `Rc` never inspects the object graph. Like
+    /// [`std::rc::Rc::clone`], this function only increments the strong count
+    /// and copies the pointer, regardless of whether the `Rc` has adopted or
+    /// been adopted by other `Rc`s.
+    ///
+    /// [`std::rc::Rc::clone`]: https://doc.rust-lang.org/stable/std/rc/struct.Rc.html#method.clone
+    ///
     /// # Examples
     ///
     /// ```
@@ -1054,6 +2537,41 @@ impl<T> Clone for Rc<T> {
         self.inner().inc_strong();
         Self::from_inner(self.ptr)
     }
+
+    /// Performs copy-assignment from `source`.
+    ///
+    /// If `self` and `source` point to the same allocation, this is a no-op and
+    /// the strong count is left unchanged.
+    ///
+    /// Otherwise, `source` is cloned before the `Rc` previously held by `self`
+    /// is dropped. If `source` is owned by a cycle which `self` keeps alive,
+    /// the new strong reference prevents the cycle from being collected when
+    /// the old `Rc` is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::Rc;
+    ///
+    /// let five = Rc::new(5);
+    /// let six = Rc::new(6);
+    ///
+    /// let mut rc = Rc::clone(&five);
+    /// rc.clone_from(&five);
+    /// assert_eq!(Rc::strong_count(&five), 2);
+    ///
+    /// rc.clone_from(&six);
+    /// assert_eq!(*rc, 6);
+    /// assert_eq!(Rc::strong_count(&five), 1);
+    /// assert_eq!(Rc::strong_count(&six), 2);
+    /// ```
+    #[inline]
+    fn clone_from(&mut self, source: &Self) {
+        if Rc::ptr_eq(self, source) {
+            return;
+        }
+        *self = Rc::clone(source);
+    }
 }
 
 impl<T: Default> Default for Rc<T> {
@@ -1123,6 +2641,27 @@ impl<T: PartialEq> PartialEq for Rc<T> {
 
 impl<T: Eq> Eq for Rc<T> {}
 
+impl<T: PartialEq> PartialEq<T> for Rc<T> {
+    /// Equality for a `Rc` and a value.
+    ///
+    /// A `Rc` is equal to a value if its inner value is equal to it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::Rc;
+    ///
+    /// let five = Rc::new(5);
+    ///
+    /// assert!(five == 5);
+    /// assert_eq!(five, 5);
+    /// ```
+    #[inline]
+    fn eq(&self, other: &T) -> bool {
+        **self == *other
+    }
+}
+
 impl<T: PartialOrd> PartialOrd for Rc<T> {
     /// Partial comparison for two `Rc`s.
     ///
@@ -1297,6 +2836,133 @@ impl<T> From<Box<T>> for Rc<T> {
     }
 }
 
+/// Formats the position of an [`Rc`] in the object graph.
+///
+/// Returned by [`Rc::debug_graph`].
+struct DebugGraph<'a, T> {
+    rc: &'a Rc<T>,
+}
+
+impl<T> fmt::Debug for DebugGraph<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts = Rc::counts(self.rc);
+        let mut node = f.debug_struct("Rc");
+        node.field("ptr", &Rc::as_ptr(self.rc))
+            .field("strong", &counts.strong)
+            .field("weak", &counts.weak);
+        #[cfg(feature = "cycle-detection")]
+        {
+            use crate::link::Kind;
+
+            let links = self.rc.inner().links().map(RefCell::borrow);
+            let links = links.iter().flat_map(|links| links.iter());
+            // Links point to the `RcBox`. Format the address of the value to
+            // match `Rc::as_ptr`.
+            let forward = links
+                .clone()
+                .filter(|(link, _)| matches!(link.kind(), Kind::Forward | Kind::Loopback))
+                .map(|(link, _)| link.as_ref().value.as_ptr());
+            let backward = links
+                .filter(|(link, _)| matches!(link.kind(), Kind::Backward))
+                .map(|(link, _)| link.as_ref().value.as_ptr());
+            node.field("forward", &DebugPtrs(forward))
+                .field("backward", &DebugPtrs(backward));
+        }
+        node.finish()
+    }
+}
+
+#[cfg(feature = "cycle-detection")]
+struct DebugPtrs<I>(I);
+
+#[cfg(feature = "cycle-detection")]
+impl<T, I> fmt::Debug for DebugPtrs<I>
+where
+    I: Iterator<Item = *const T> + Clone,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.0.clone()).finish()
+    }
+}
+
+/// A wrapper around an [`Rc`] which hashes and compares by allocation address.
+///
+/// `Rc` forwards [`Hash`] and [`Eq`] to its inner value. `PtrKey` instead
+/// treats two `Rc`s as equal if they point to the same allocation (see
+/// [`Rc::ptr_eq`]), which is useful for deduplicating the roots of an object
+/// graph in a `HashSet`. `T` does not need to implement `Hash` or `Eq`.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashSet;
+///
+/// use cactusref::{PtrKey, Rc};
+///
+/// let first = Rc::new(5);
+/// let second = Rc::new(5);
+/// let roots = vec![Rc::clone(&first), Rc::clone(&second), Rc::clone(&first)];
+///
+/// let roots = roots.into_iter().map(PtrKey).collect::<HashSet<_>>();
+/// assert_eq!(roots.len(), 2);
+/// assert!(roots.contains(&PtrKey(first)));
+/// ```
+pub struct PtrKey<T>(pub Rc<T>);
+
+impl<T> PtrKey<T> {
+    /// Consumes the `PtrKey`, returning the wrapped `Rc`.
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> Rc<T> {
+        self.0
+    }
+}
+
+impl<T> Clone for PtrKey<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self(Rc::clone(&self.0))
+    }
+}
+
+impl<T> fmt::Debug for PtrKey<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PtrKey").field(&self.0.ptr.as_ptr()).finish()
+    }
+}
+
+impl<T> PartialEq for PtrKey<T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl<T> Eq for PtrKey<T> {}
+
+impl<T> Hash for PtrKey<T> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Rc::ptr_hash(&self.0, state);
+    }
+}
+
+impl<T> From<Rc<T>> for PtrKey<T> {
+    #[inline]
+    fn from(rc: Rc<T>) -> Self {
+        Self(rc)
+    }
+}
+
+impl<T> Deref for PtrKey<T> {
+    type Target = Rc<T>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
 /// `Weak` is a version of [`Rc`] that holds a non-owning reference to the
 /// managed allocation. The allocation is accessed by calling [`upgrade`] on the `Weak`
 /// pointer, which returns an <code>[Option]<[Rc]\<T>></code>.
@@ -1328,6 +2994,11 @@ pub struct Weak<T> {
     phantom: PhantomData<RcBox<T>>,
 }
 
+// `Weak`s share the reference counts of their `Rc`s, so they must not cross
+// threads either. See the negative impls for `Rc`.
+impl<T> !Send for Weak<T> {}
+impl<T> !Sync for Weak<T> {}
+
 /// `Weak` is not `Send`.
 ///
 /// ```compile_fail
@@ -1336,6 +3007,16 @@ pub struct Weak<T> {
 /// let weak = Weak::<usize>::new();
 /// requires_send(weak);
 /// ```
+///
+/// Even if `T` is `Send`, a `Weak` cannot be moved to another thread:
+///
+/// ```compile_fail,E0277
+/// use std::thread;
+/// use cactusref::Rc;
+/// let rc = Rc::<usize>::new(1);
+/// let weak = Rc::downgrade(&rc);
+/// thread::spawn(move || assert!(weak.upgrade().is_some()));
+/// ```
 mod weak_is_not_send {}
 
 /// `Weak` is not `Sync`.
@@ -1348,6 +3029,14 @@ mod weak_is_not_send {}
 /// ```
 mod weak_is_not_sync {}
 
+/// `Weak` requires a `Sized` value, like [`Rc`].
+///
+/// ```compile_fail,E0277
+/// use cactusref::Weak;
+/// fn slice(weak: Weak<[u8]>) {}
+/// ```
+mod weak_is_not_unsized {}
+
 impl<T> Weak<T> {
     /// Constructs a new `Weak<T>`, without allocating any memory.
     /// Calling [`upgrade`] on the return value always gives [`None`].
@@ -1533,6 +3222,15 @@ impl<T> Weak<T> {
     ///
     /// Returns [`None`] if the inner value has since been dropped.
     ///
+    /// # Performance
+    ///
+    /// Upgrading does not inspect the object graph, even if the allocation has
+    /// adopted other `Rc`s. Members of a collected cycle are marked dead before
+    /// their values are dropped, so `upgrade` only checks and increments the
+    /// strong count, like [`std::rc::Weak::upgrade`].
+    ///
+    /// [`std::rc::Weak::upgrade`]: alloc::rc::Weak::upgrade
+    ///
     /// # Examples
     ///
     /// ```
@@ -1551,6 +3249,7 @@ impl<T> Weak<T> {
     ///
     /// assert!(weak_five.upgrade().is_none());
     /// ```
+    #[inline]
     #[must_use]
     pub fn upgrade(&self) -> Option<Rc<T>> {
         let inner = self.inner()?;
@@ -1562,35 +3261,165 @@ impl<T> Weak<T> {
         }
     }
 
+    /// Attempts to upgrade the `Weak` pointer and calls `f` with a reference to
+    /// the inner value, returning its result.
+    ///
+    /// Returns [`None`] without calling `f` if the inner value has since been
+    /// dropped.
+    ///
+    /// The temporary strong reference is released when `f` returns, so no `Rc`
+    /// can be accidentally retained, e.g. by storing the result of
+    /// [`upgrade`] in a collection. The strong count is still incremented for
+    /// the duration of the call so the value stays alive if `f` drops the
+    /// other `Rc`s to the allocation.
+    ///
+    /// [`upgrade`]: Weak::upgrade
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::Rc;
+    ///
+    /// let five = Rc::new(5);
+    /// let weak_five = Rc::downgrade(&five);
+    ///
+    /// assert_eq!(weak_five.with_upgraded(|five| five + 1), Some(6));
+    /// assert_eq!(Rc::strong_count(&five), 1);
+    ///
+    /// drop(five);
+    ///
+    /// assert_eq!(weak_five.with_upgraded(|five| five + 1), None);
+    /// ```
+    #[inline]
+    pub fn with_upgraded<R, F>(&self, f: F) -> Option<R>
+    where
+        F: FnOnce(&T) -> R,
+    {
+        let rc = self.upgrade()?;
+        Some(f(&rc))
+    }
+
+    /// Attempts to upgrade the `Weak` pointer to an [`Rc`], returning `default`
+    /// if the inner value has since been dropped.
+    ///
+    /// The fallback is used for `Weak`s created with [`Weak::new`] and for
+    /// `Weak`s that point to members of a deallocated cycle.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::Rc;
+    ///
+    /// let five = Rc::new(5);
+    /// let weak_five = Rc::downgrade(&five);
+    ///
+    /// assert_eq!(*weak_five.upgrade_or(Rc::new(6)), 5);
+    ///
+    /// drop(five);
+    ///
+    /// assert_eq!(*weak_five.upgrade_or(Rc::new(6)), 6);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn upgrade_or(&self, default: Rc<T>) -> Rc<T> {
+        self.upgrade().unwrap_or(default)
+    }
+
+    /// Attempts to upgrade the `Weak` pointer to an [`Rc`], computing a
+    /// fallback from `f` if the inner value has since been dropped.
+    ///
+    /// `f` is only called if the upgrade fails, which happens for `Weak`s
+    /// created with [`Weak::new`] and for `Weak`s that point to members of a
+    /// deallocated cycle.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::Rc;
+    ///
+    /// let five = Rc::new(5);
+    /// let weak_five = Rc::downgrade(&five);
+    ///
+    /// assert_eq!(*weak_five.upgrade_or_else(|| Rc::new(6)), 5);
+    ///
+    /// drop(five);
+    ///
+    /// assert_eq!(*weak_five.upgrade_or_else(|| Rc::new(6)), 6);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn upgrade_or_else<F>(&self, f: F) -> Rc<T>
+    where
+        F: FnOnce() -> Rc<T>,
+    {
+        self.upgrade().unwrap_or_else(f)
+    }
+
     /// Gets the number of strong (`Rc`) pointers pointing to this allocation.
     ///
     /// If `self` was created using [`Weak::new`], this will return 0.
+    ///
+    /// If the allocation was a member of an orphaned cycle which has been
+    /// collected, this will return 0. Every member of a cycle is marked dead
+    /// before any value in the cycle is dropped, so this also returns 0 when
+    /// called from the [`Drop`] implementation of a value in the cycle.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "cycle-detection")]
+    /// # fn main() {
+    /// use std::cell::RefCell;
+    ///
+    /// use cactusref::{Adopt, Rc};
+    ///
+    /// struct Node {
+    ///     next: RefCell<Option<Rc<Node>>>,
+    /// }
+    ///
+    /// let node = Rc::new(Node { next: RefCell::new(None) });
+    /// let clone = Rc::clone(&node);
+    /// unsafe {
+    ///     Rc::adopt_unchecked(&node, &clone);
+    /// }
+    /// *node.next.borrow_mut() = Some(clone);
+    /// let weak = Rc::downgrade(&node);
+    /// assert_eq!(weak.strong_count(), 2);
+    ///
+    /// // Collect the orphaned cycle.
+    /// drop(node);
+    /// assert_eq!(weak.strong_count(), 0);
+    /// assert_eq!(weak.weak_count(), 0);
+    /// # }
+    /// # #[cfg(not(feature = "cycle-detection"))]
+    /// # fn main() {}
+    /// ```
     #[must_use]
     pub fn strong_count(&self) -> usize {
-        if let Some(inner) = self.inner() {
-            if inner.is_uninit() {
-                0
-            } else {
-                inner.strong()
-            }
-        } else {
-            0
+        match self.inner().map(|inner| inner.state()) {
+            Some(RcState::Live(strong)) => strong,
+            Some(RcState::Dead | RcState::Uninit) | None => 0,
         }
     }
 
     /// Gets the number of `Weak` pointers pointing to this allocation.
     ///
-    /// If no strong pointers remain, this will return zero.
+    /// If no strong pointers remain, this will return zero. This matches
+    /// [`std::rc::Weak::weak_count`] and includes members of a collected
+    /// orphaned cycle: once the value is dropped, `weak_count` returns zero
+    /// even though the surviving `Weak`s keep the allocation alive until they
+    /// are dropped.
+    ///
+    /// The number of surviving `Weak`s is not reported after the value is
+    /// dropped, so `Weak` behaves the same whether its value was dropped by
+    /// the last `Rc` or by collecting a cycle.
+    ///
+    /// [`std::rc::Weak::weak_count`]: alloc::rc::Weak::weak_count
     #[must_use]
     pub fn weak_count(&self) -> usize {
-        self.inner().map_or(0, |inner| {
-            if inner.is_uninit() {
-                0
-            } else if inner.strong() > 0 {
-                inner.weak() - 1 // subtract the implicit weak ptr
-            } else {
-                0
-            }
+        self.inner().map_or(0, |inner| match inner.state() {
+            RcState::Live(_) => inner.weak() - 1, // subtract the implicit weak ptr
+            RcState::Dead | RcState::Uninit => 0,
         })
     }
 
@@ -1661,6 +3490,38 @@ impl<T> Weak<T> {
     pub fn ptr_eq(&self, other: &Self) -> bool {
         self.ptr.as_ptr() == other.ptr.as_ptr()
     }
+
+    /// Returns `true` if this `Weak` points to the same allocation as `rc`
+    /// (similar to [`ptr::eq`]).
+    ///
+    /// Unlike upgrading the `Weak` and comparing the result with
+    /// [`Rc::ptr_eq`], this does not touch the strong count.
+    ///
+    /// A `Weak` created with [`Weak::new`] does not point to any allocation
+    /// and never points to an `Rc`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cactusref::{Rc, Weak};
+    ///
+    /// let five = Rc::new(5);
+    /// let weak_five = Rc::downgrade(&five);
+    /// assert!(weak_five.points_to(&five));
+    ///
+    /// let other_five = Rc::new(5);
+    /// assert!(!weak_five.points_to(&other_five));
+    ///
+    /// let empty = Weak::new();
+    /// assert!(!empty.points_to(&five));
+    /// ```
+    ///
+    /// [`ptr::eq`]: core::ptr::eq
+    #[inline]
+    #[must_use]
+    pub fn points_to(&self, rc: &Rc<T>) -> bool {
+        self.ptr.as_ptr() == rc.ptr.as_ptr()
+    }
 }
 
 unsafe impl<#[may_dangle] T> Drop for Weak<T> {
@@ -1759,6 +3620,84 @@ impl<T> Default for Weak<T> {
     }
 }
 
+/// A borrowed view of an [`Rc`] which behaves like a [`Weak`] pointer without
+/// owning a weak reference.
+///
+/// Creating and dropping a `WeakRef` does not modify the weak count of the
+/// allocation, which makes it cheaper than [`Rc::downgrade`] in hot
+/// inspection loops. A `WeakRef` is tied to the lifetime of the borrowed
+/// `Rc`. Use [`WeakRef::to_weak`] to obtain an owned `Weak`.
+///
+/// `WeakRef`s are created by [`Rc::weak_ref`].
+pub struct WeakRef<'a, T> {
+    ptr: NonNull<RcBox<T>>,
+    phantom: PhantomData<&'a Rc<T>>,
+}
+
+impl<T> WeakRef<'_, T> {
+    /// Borrow this `WeakRef` as a `Weak` which must not be dropped.
+    fn as_weak(self) -> ManuallyDrop<Weak<T>> {
+        ManuallyDrop::new(Weak {
+            ptr: self.ptr,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Attempts to upgrade the `WeakRef` to an [`Rc`], delaying dropping of
+    /// the inner value if successful.
+    ///
+    /// Returns [`None`] if the inner value has since been dropped, which can
+    /// only be observed from a [`Drop`] implementation of a value in an object
+    /// graph that is being collected.
+    ///
+    /// See [`Weak::upgrade`].
+    #[inline]
+    #[must_use]
+    pub fn upgrade(&self) -> Option<Rc<T>> {
+        self.as_weak().upgrade()
+    }
+
+    /// Gets the number of strong (`Rc`) pointers pointing to this allocation.
+    ///
+    /// See [`Weak::strong_count`].
+    #[must_use]
+    pub fn strong_count(&self) -> usize {
+        self.as_weak().strong_count()
+    }
+
+    /// Gets the number of [`Weak`] pointers pointing to this allocation.
+    ///
+    /// `WeakRef`s are not counted.
+    ///
+    /// See [`Weak::weak_count`].
+    #[must_use]
+    pub fn weak_count(&self) -> usize {
+        self.as_weak().weak_count()
+    }
+
+    /// Creates a new owned [`Weak`] pointer to this allocation, incrementing
+    /// the weak count.
+    #[must_use]
+    pub fn to_weak(&self) -> Weak<T> {
+        Weak::clone(&self.as_weak())
+    }
+}
+
+impl<T> Clone for WeakRef<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for WeakRef<'_, T> {}
+
+impl<T> fmt::Debug for WeakRef<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(WeakRef)")
+    }
+}
+
 // NOTE: We checked_add here to deal with mem::forget safely. In particular
 // if you mem::forget Rcs (or Weaks), the ref-count can overflow, and then
 // you can free the allocation while outstanding Rcs (or Weaks) exist.
@@ -1768,6 +3707,22 @@ impl<T> Default for Weak<T> {
 // This should have negligible overhead since you don't actually need to
 // clone these much in Rust thanks to ownership and move-semantics.
 
+/// The lifecycle state of an `RcBox`, decoded from its strong count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RcState {
+    /// The `RcBox` is owned by the contained number of strong references. The
+    /// `value` and `links` fields are inhabited.
+    Live(usize),
+    /// All strong references have been released, but the `value` and `links`
+    /// fields have not yet been moved out of the `RcBox`.
+    Dead,
+    /// The `value` and `links` fields have been moved out of the `RcBox` to be
+    /// dropped and must not be accessed.
+    ///
+    /// This state is encoded with a strong count of `usize::MAX`.
+    Uninit,
+}
+
 #[doc(hidden)]
 pub(crate) trait RcInnerPtr {
     fn weak_ref(&self) -> &Cell<usize>;
@@ -1827,18 +3782,32 @@ pub(crate) trait RcInnerPtr {
         self.weak_ref().set(self.weak() - 1);
     }
 
+    #[inline]
+    fn state(&self) -> RcState {
+        match self.strong() {
+            0 => RcState::Dead,
+            usize::MAX => RcState::Uninit,
+            strong => RcState::Live(strong),
+        }
+    }
+
+    /// Returns whether the `RcBox` has no strong references, which includes
+    /// `RcBox`es that are uninitialized.
     #[inline]
     fn is_dead(&self) -> bool {
-        self.strong() == 0 || self.is_uninit()
+        !matches!(self.state(), RcState::Live(_))
     }
 
     #[inline]
     fn is_uninit(&self) -> bool {
-        self.strong() == usize::MAX
+        matches!(self.state(), RcState::Uninit)
     }
 
+    /// Transition a dead `RcBox` to the uninitialized state before moving its
+    /// `value` and `links` fields out.
     #[inline]
     fn make_uninit(&self) {
+        debug_assert_eq!(self.state(), RcState::Dead);
         self.strong_ref().set(usize::MAX);
     }
 }
@@ -1855,7 +3824,7 @@ impl<T> RcInnerPtr for RcBox<T> {
     }
 }
 
-impl<'a> RcInnerPtr for WeakInner<'a> {
+impl RcInnerPtr for WeakInner<'_> {
     #[inline(always)]
     fn weak_ref(&self) -> &Cell<usize> {
         self.weak
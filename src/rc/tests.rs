@@ -1,20 +1,64 @@
 use std::boxed::Box;
 use std::cell::RefCell;
 use std::format;
-use std::mem::size_of;
+use std::mem::{self, size_of};
+use std::string::String;
 
-use super::{Rc, RcBox, Weak};
+use super::{Counts, PtrKey, Rc, RcBox, RcInnerPtr, RcState, Weak};
+#[cfg(feature = "cycle-detection")]
+use crate::{Adopt, MakeMutError};
+use crate::{RefCountOverflow, UnwrapError};
+
+// Build a ring of `Rc`s in which every node owns and adopts the next node.
+// `set_next` stores the strong reference to the next node in a node.
+#[cfg(feature = "cycle-detection")]
+fn ring<T, const N: usize>(values: [T; N], set_next: impl Fn(&Rc<T>, Rc<T>)) -> [Rc<T>; N] {
+    let nodes = values.map(Rc::new);
+    for (idx, node) in nodes.iter().enumerate() {
+        let next = Rc::clone(&nodes[(idx + 1) % N]);
+        unsafe {
+            Rc::adopt_unchecked(node, &next);
+        }
+        set_next(node, next);
+    }
+    nodes
+}
 
 // This test documents the size of `RcBox`, the inner allocation of the `Rc`.
 //
 // Feel free to change this test so it passes, but document in PRs when it
 // changes and why it does.
 #[test]
+#[cfg(all(feature = "cycle-detection", not(feature = "graph-algorithms")))]
 fn size_of_rcbox() {
+    // Adoption links are boxed, so cycle detection costs one pointer per
+    // `RcBox` until the `Rc` is adopted.
     #[cfg(target_pointer_width = "64")]
-    assert_eq!(size_of::<RcBox<i32>>(), 64);
-    #[cfg(target_pointer_width = "32")]
     assert_eq!(size_of::<RcBox<i32>>(), 32);
+    #[cfg(target_pointer_width = "32")]
+    assert_eq!(size_of::<RcBox<i32>>(), 16);
+}
+
+// The mark byte is padded to the alignment of the adoption links, so graph
+// algorithms cost one more word per `RcBox`.
+#[test]
+#[cfg(feature = "graph-algorithms")]
+fn size_of_rcbox() {
+    #[cfg(target_pointer_width = "64")]
+    assert_eq!(size_of::<RcBox<i32>>(), 40);
+    #[cfg(target_pointer_width = "32")]
+    assert_eq!(size_of::<RcBox<i32>>(), 20);
+}
+
+// Without cycle detection, `RcBox` has the same layout as the inner allocation
+// of `std::rc::Rc`.
+#[test]
+#[cfg(not(feature = "cycle-detection"))]
+fn size_of_rcbox() {
+    #[cfg(target_pointer_width = "64")]
+    assert_eq!(size_of::<RcBox<i32>>(), 24);
+    #[cfg(target_pointer_width = "32")]
+    assert_eq!(size_of::<RcBox<i32>>(), 12);
 }
 
 #[test]
@@ -60,6 +104,21 @@ fn test_dead() {
     assert!(y.upgrade().is_none());
 }
 
+#[test]
+fn weak_self_cyclic() {
+    struct Cycle {
+        x: RefCell<Option<Weak<Cycle>>>,
+    }
+
+    let a = Rc::new(Cycle {
+        x: RefCell::new(None),
+    });
+    let b = Rc::downgrade(&a.clone());
+    *a.x.borrow_mut() = Some(b);
+
+    // hopefully we don't double-free (or leak)...
+}
+
 #[test]
 fn is_unique() {
     let x = Rc::new(3);
@@ -147,6 +206,106 @@ fn try_unwrap() {
     assert_eq!(Rc::try_unwrap(x), Ok(5));
 }
 
+#[test]
+fn try_into_box() {
+    let x = Rc::new(String::from("unique"));
+    assert_eq!(
+        Rc::try_into_box(x).as_deref().map(String::as_str),
+        Ok("unique")
+    );
+
+    let x = Rc::new(String::from("shared"));
+    let y = Rc::clone(&x);
+    let x = Rc::try_into_box(x).unwrap_err();
+    assert_eq!(Rc::strong_count(&x), 2);
+    drop(y);
+
+    let w = Rc::downgrade(&x);
+    let x = Rc::try_into_box(x).unwrap_err();
+    assert_eq!(Rc::weak_count(&x), 1);
+    drop(w);
+
+    assert_eq!(*Rc::try_into_box(x).unwrap(), "shared");
+}
+
+#[test]
+#[cfg(feature = "cycle-detection")]
+fn try_into_box_graphed() {
+    let first = Rc::new(String::from("first"));
+    let second = Rc::new(String::from("second"));
+    unsafe {
+        Rc::adopt_unchecked(&first, &second);
+        Rc::adopt_unchecked(&second, &first);
+    }
+    // Each `Rc` is the only strong reference to its allocation, but the
+    // allocations are also owned by the cycle.
+    let first = Rc::try_into_box(first).unwrap_err();
+    let second = Rc::try_into_box(second).unwrap_err();
+    assert_eq!(*first, "first");
+    assert_eq!(*second, "second");
+}
+
+#[test]
+fn map_value() {
+    // `RcBox<String>` and `RcBox<i32>` have different layouts.
+    let x = Rc::new(5_i32);
+    let x = Rc::map_value(x, |x| format!("{x}")).unwrap();
+    assert_eq!(*x, "5");
+    assert_eq!(Rc::strong_count(&x), 1);
+
+    // `RcBox<u64>` and `RcBox<i64>` have the same layout, so the allocation is
+    // reused.
+    let x = Rc::new(7_u64);
+    let addr = Rc::as_ptr(&x) as usize;
+    let x = Rc::map_value(x, |x| -i64::try_from(x).unwrap()).unwrap();
+    assert_eq!(*x, -7);
+    assert_eq!(Rc::as_ptr(&x) as usize, addr);
+    let w = Rc::downgrade(&x);
+    drop(x);
+    assert!(w.upgrade().is_none());
+
+    let x = Rc::new(1_i32);
+    let y = Rc::clone(&x);
+    let x = Rc::map_value(x, |_| unreachable!()).unwrap_err();
+    assert_eq!(Rc::strong_count(&x), 2);
+    drop(y);
+
+    let w = Rc::downgrade(&x);
+    let x = Rc::map_value(x, |_| unreachable!()).unwrap_err();
+    assert_eq!(Rc::weak_count(&x), 1);
+    drop(w);
+
+    let x: Rc<String> = Rc::map_value(x, |x| format!("{x}")).unwrap();
+    assert_eq!(*x, "1");
+}
+
+#[test]
+fn map_value_panic() {
+    use std::panic::{self, AssertUnwindSafe};
+
+    // The allocation is freed, which Miri checks.
+    let x = Rc::new(String::from("panic"));
+    let result = panic::catch_unwind(AssertUnwindSafe(move || {
+        Rc::map_value(x, |_| -> String { panic!("map_value panicked") })
+    }));
+    assert!(result.is_err());
+}
+
+#[test]
+#[cfg(feature = "cycle-detection")]
+fn map_value_graphed() {
+    let first = Rc::new(1);
+    let second = Rc::new(2);
+    unsafe {
+        Rc::adopt_unchecked(&first, &second);
+        Rc::adopt_unchecked(&second, &first);
+    }
+    let first = Rc::map_value(first, |_| unreachable!()).unwrap_err();
+    let second = Rc::map_value(second, |_| unreachable!()).unwrap_err();
+    assert_eq!(*first, 1);
+    assert_eq!(*second, 2);
+}
+
 #[test]
 fn into_from_raw() {
     let x = Rc::new(Box::new("hello"));
@@ -182,6 +341,64 @@ fn into_from_weak_raw() {
     }
 }
 
+#[test]
+fn from_raw_parts_round_trip() {
+    let x = Rc::new(String::from("hello"));
+    let y = Rc::clone(&x);
+    let weak = Rc::downgrade(&x);
+    let (strong, weak_count) = (Rc::strong_count(&x), Rc::weak_count(&x));
+
+    let ptr = Rc::into_raw(x);
+    let y_ptr = Rc::into_raw(y);
+    assert_eq!(ptr, y_ptr);
+    unsafe {
+        let x = Rc::from_raw_parts(ptr, strong, weak_count);
+        assert_eq!(Rc::strong_count(&x), 2);
+        assert_eq!(Rc::weak_count(&x), 1);
+        drop(Rc::from_raw(y_ptr));
+        assert_eq!(*x, "hello");
+    }
+    assert!(weak.upgrade().is_none());
+
+    // Foreign code released a strong and a weak reference without dropping
+    // them as `Rc` and `Weak`.
+    let x = Rc::new(String::from("hello"));
+    let ptr = Rc::into_raw(Rc::clone(&x));
+    mem::forget(Rc::downgrade(&x));
+    mem::forget(x);
+    unsafe {
+        let x = Rc::from_raw_parts(ptr, 1, 0);
+        assert_eq!(Rc::counts(&x).strong, 1);
+        assert_eq!(Rc::counts(&x).weak, 0);
+        assert_eq!(Rc::try_unwrap(x).as_deref(), Ok("hello"));
+    }
+}
+
+#[test]
+fn weak_as_ptr() {
+    // Live
+    let x = Rc::new(String::from("hello"));
+    let weak = Rc::downgrade(&x);
+    let ptr = weak.as_ptr();
+    assert_eq!(ptr, Rc::as_ptr(&x));
+    assert_eq!(unsafe { &*ptr }, "hello");
+    let raw = Weak::into_raw(weak);
+    assert_eq!(raw, ptr);
+
+    // Dropped
+    drop(x);
+    let weak = unsafe { Weak::from_raw(raw) };
+    assert_eq!(weak.as_ptr(), ptr);
+    assert_eq!(Weak::into_raw(weak), ptr);
+    drop(unsafe { Weak::from_raw(raw) });
+
+    // Empty
+    let weak = Weak::<String>::new();
+    let ptr = weak.as_ptr();
+    assert_eq!(Weak::new().as_ptr(), ptr);
+    assert_eq!(Weak::into_raw(weak), ptr);
+}
+
 #[test]
 fn get_mut() {
     let mut x = Rc::new(3);
@@ -195,6 +412,34 @@ fn get_mut() {
     assert!(Rc::get_mut(&mut x).is_none());
 }
 
+#[test]
+fn as_uninit_mut_reinitializes_in_place() {
+    use std::cell::Cell;
+
+    struct Tracked<'a>(usize, &'a Cell<usize>);
+
+    impl Drop for Tracked<'_> {
+        fn drop(&mut self) {
+            self.1.set(self.1.get() + 1);
+        }
+    }
+
+    let drops = Cell::new(0);
+    let mut x = Rc::new(Tracked(1, &drops));
+    let ptr = Rc::as_ptr(&x);
+    unsafe {
+        let slot = Rc::as_uninit_mut(&mut x);
+        slot.assume_init_drop();
+        slot.write(Tracked(2, &drops));
+    }
+    assert_eq!(drops.get(), 1);
+    assert_eq!(x.0, 2);
+    assert_eq!(Rc::as_ptr(&x), ptr);
+
+    drop(x);
+    assert_eq!(drops.get(), 2);
+}
+
 #[test]
 fn test_cowrc_clone_make_unique() {
     let mut cow0 = Rc::new(75);
@@ -285,6 +530,22 @@ fn test_ptr_eq() {
     assert!(!Rc::ptr_eq(&five, &other_five));
 }
 
+#[test]
+fn eq_inner_value() {
+    let five = Rc::new(5_i32);
+
+    assert!(five == 5);
+    assert!(five != 6);
+    assert_eq!(five, 5);
+    assert_ne!(five, 6);
+    // Comparing two `Rc`s still compares their inner values.
+    assert_eq!(five, Rc::new(5));
+    assert_ne!(five, Rc::new(6));
+
+    let name = Rc::new(String::from("cactus"));
+    assert_eq!(name, String::from("cactus"));
+}
+
 #[test]
 fn test_from_box() {
     let b: Box<u32> = Box::new(123);
@@ -292,3 +553,1738 @@ fn test_from_box() {
 
     assert_eq!(*r, 123);
 }
+
+#[test]
+fn upgrade_or_live() {
+    let five = Rc::new(5);
+    let weak = Rc::downgrade(&five);
+
+    let upgraded = weak.upgrade_or(Rc::new(6));
+    assert!(Rc::ptr_eq(&five, &upgraded));
+    assert_eq!(Rc::strong_count(&five), 2);
+
+    let upgraded = weak.upgrade_or_else(|| unreachable!("upgrade of live Rc failed"));
+    assert!(Rc::ptr_eq(&five, &upgraded));
+    assert_eq!(Rc::strong_count(&five), 3);
+}
+
+#[test]
+fn upgrade_or_dropped() {
+    let five = Rc::new(5);
+    let weak = Rc::downgrade(&five);
+    drop(five);
+
+    assert_eq!(*weak.upgrade_or(Rc::new(6)), 6);
+    assert_eq!(*weak.upgrade_or_else(|| Rc::new(7)), 7);
+
+    let empty = Weak::<i32>::new();
+    assert_eq!(*empty.upgrade_or(Rc::new(8)), 8);
+    assert_eq!(*empty.upgrade_or_else(|| Rc::new(9)), 9);
+}
+
+#[test]
+#[cfg(feature = "cycle-detection")]
+fn upgrade_or_collected_cycle() {
+    #[derive(Default)]
+    struct Node {
+        next: RefCell<Option<Rc<Node>>>,
+        data: i32,
+    }
+
+    let [first, second] = ring(
+        [
+            Node {
+                next: RefCell::new(None),
+                data: 1,
+            },
+            Node {
+                next: RefCell::new(None),
+                data: 2,
+            },
+        ],
+        |node, next| *node.next.borrow_mut() = Some(next),
+    );
+    let weak = Rc::downgrade(&first);
+    assert_eq!(weak.upgrade_or_else(|| Rc::new(Node::default())).data, 1);
+
+    drop(first);
+    drop(second);
+
+    assert!(weak.upgrade().is_none());
+    assert_eq!(weak.upgrade_or(Rc::new(Node::default())).data, 0);
+    assert_eq!(weak.upgrade_or_else(|| Rc::new(Node::default())).data, 0);
+}
+
+#[test]
+#[cfg(feature = "cycle-detection")]
+fn adopt_does_not_change_strong_count() {
+    let first = Rc::new(1);
+    let second = Rc::new(2);
+    let first_clone = Rc::clone(&first);
+    let second_clone = Rc::clone(&second);
+
+    unsafe {
+        Rc::adopt_unchecked(&first, &second_clone);
+        Rc::adopt_unchecked(&second, &first_clone);
+    }
+    assert_eq!(Rc::strong_count(&first), 2);
+    assert_eq!(Rc::strong_count(&second), 2);
+
+    Rc::unadopt(&first, &second_clone);
+    Rc::unadopt(&second, &first_clone);
+    assert_eq!(Rc::strong_count(&first), 2);
+    assert_eq!(Rc::strong_count(&second), 2);
+
+    drop(first_clone);
+    drop(second_clone);
+    assert_eq!(Rc::strong_count(&first), 1);
+    assert_eq!(Rc::strong_count(&second), 1);
+}
+
+#[test]
+#[cfg(feature = "cycle-detection")]
+fn links_allocated_on_first_adopt() {
+    let first = Rc::new(1);
+    let second = Rc::new(2);
+    assert!(first.inner().links().is_none());
+    assert!(second.inner().links().is_none());
+
+    unsafe {
+        Rc::adopt_unchecked(&first, &second);
+    }
+    assert!(first.inner().has_links());
+    assert!(second.inner().has_links());
+
+    Rc::unadopt(&first, &second);
+    assert!(first.inner().links().is_some());
+    assert!(!first.inner().has_links());
+    assert!(!second.inner().has_links());
+}
+
+#[test]
+#[cfg(feature = "cycle-detection")]
+fn try_unwrap_frees_links() {
+    let first = Rc::new(String::from("first"));
+    let second = Rc::new(String::from("second"));
+    unsafe {
+        Rc::adopt_unchecked(&first, &second);
+    }
+    assert_eq!(Rc::try_unwrap(second).unwrap(), "second");
+    assert!(!first.inner().has_links());
+
+    let mut first = first;
+    let weak = Rc::downgrade(&first);
+    let third = Rc::new(String::from("third"));
+    unsafe {
+        Rc::adopt_unchecked(&third, &first);
+    }
+    Rc::make_mut(&mut first).push_str("-stolen");
+    assert!(weak.upgrade().is_none());
+    assert!(!third.inner().has_links());
+    assert!(!first.inner().has_links());
+    assert_eq!(*first, "first-stolen");
+}
+
+#[test]
+fn try_unwrap_detailed() {
+    let x = Rc::new(3);
+    assert_eq!(Rc::try_unwrap_detailed(x).ok(), Some(3));
+
+    let x = Rc::new(4);
+    let _w = Rc::downgrade(&x);
+    assert_eq!(Rc::try_unwrap_detailed(x).ok(), Some(4));
+}
+
+#[test]
+fn try_unwrap_detailed_shared_strong() {
+    let x = Rc::new(4);
+    let y = Rc::clone(&x);
+    let _z = Rc::clone(&y);
+    let (x, err) = Rc::try_unwrap_detailed(x).unwrap_err();
+    assert_eq!(err, UnwrapError::SharedStrong(3));
+    assert!(Rc::ptr_eq(&x, &y));
+}
+
+#[test]
+#[cfg(feature = "cycle-detection")]
+fn try_unwrap_detailed_cycle_member() {
+    #[derive(Debug)]
+    struct Node {
+        next: RefCell<Option<Rc<Node>>>,
+    }
+
+    let [first, second] = ring(
+        [
+            Node {
+                next: RefCell::new(None),
+            },
+            Node {
+                next: RefCell::new(None),
+            },
+        ],
+        |node, next| *node.next.borrow_mut() = Some(next),
+    );
+
+    // `second` is externally owned, so `first` is only owned by the cycle.
+    let (first, err) = Rc::try_unwrap_detailed(first).unwrap_err();
+    assert_eq!(err, UnwrapError::CycleMember);
+
+    // An external clone of `first` makes it shared.
+    let external = Rc::clone(&first);
+    let (first, err) = Rc::try_unwrap_detailed(first).unwrap_err();
+    assert_eq!(err, UnwrapError::SharedStrong(3));
+
+    drop(external);
+    drop(first);
+    drop(second);
+}
+
+#[test]
+fn clone_from_same_pointer() {
+    let five = Rc::new(5);
+    let mut rc = Rc::clone(&five);
+    rc.clone_from(&five);
+    assert!(Rc::ptr_eq(&rc, &five));
+    assert_eq!(Rc::strong_count(&five), 2);
+}
+
+#[test]
+fn clone_from_different_pointer() {
+    let five = Rc::new(5);
+    let six = Rc::new(6);
+    let weak = Rc::downgrade(&five);
+    let mut rc = Rc::clone(&five);
+    drop(five);
+
+    rc.clone_from(&six);
+    assert!(Rc::ptr_eq(&rc, &six));
+    assert_eq!(Rc::strong_count(&six), 2);
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+#[cfg(feature = "cycle-detection")]
+fn clone_from_cycle_member() {
+    struct Node {
+        next: RefCell<Option<Rc<Node>>>,
+        data: i32,
+    }
+
+    let [first, second] = ring(
+        [
+            Node {
+                next: RefCell::new(None),
+                data: 1,
+            },
+            Node {
+                next: RefCell::new(None),
+                data: 2,
+            },
+        ],
+        |node, next| *node.next.borrow_mut() = Some(next),
+    );
+    drop(second);
+
+    // `handle` is the only external owner of the cycle and `source` is owned
+    // by a member of the cycle.
+    let mut handle = first;
+    let source: *const Rc<Node> = handle.next.borrow().as_ref().unwrap();
+    unsafe {
+        handle.clone_from(&*source);
+    }
+    assert_eq!(handle.data, 2);
+    assert_eq!(handle.next.borrow().as_ref().unwrap().data, 1);
+    assert_eq!(Rc::strong_count(&handle), 2);
+
+    let weak = Rc::downgrade(&handle);
+    drop(handle);
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn counts() {
+    let live = |strong, weak| Counts {
+        strong,
+        weak,
+        dead: false,
+        uninit: false,
+    };
+
+    let x = Rc::new(5);
+    assert_eq!(Rc::counts(&x), live(1, 0));
+    let y = Rc::clone(&x);
+    assert_eq!(Rc::counts(&x), live(2, 0));
+    let w = Rc::downgrade(&x);
+    assert_eq!(Rc::counts(&x), live(2, 1));
+    drop(y);
+    assert_eq!(Rc::counts(&x), live(1, 1));
+    drop(w);
+    assert_eq!(Rc::counts(&x), live(1, 0));
+}
+
+#[test]
+#[cfg(feature = "cycle-detection")]
+fn counts_during_cycle_collection() {
+    use std::cell::Cell;
+
+    struct Node {
+        next: RefCell<Option<Rc<Node>>>,
+        observed: Rc<Cell<Option<Counts>>>,
+    }
+
+    impl Drop for Node {
+        fn drop(&mut self) {
+            if let Some(next) = self.next.get_mut() {
+                self.observed.set(Some(Rc::counts(next)));
+            }
+        }
+    }
+
+    let observed = Rc::new(Cell::new(None));
+    let [first, second] = ring(
+        [
+            Node {
+                next: RefCell::new(None),
+                observed: Rc::clone(&observed),
+            },
+            Node {
+                next: RefCell::new(None),
+                observed: Rc::clone(&observed),
+            },
+        ],
+        |node, next| *node.next.borrow_mut() = Some(next),
+    );
+    let weaks = [Rc::downgrade(&first), Rc::downgrade(&second)];
+    assert_eq!(
+        Rc::counts(&first),
+        Counts {
+            strong: 2,
+            weak: 1,
+            dead: false,
+            uninit: false,
+        }
+    );
+
+    drop(first);
+    drop(second);
+
+    assert!(weaks.iter().all(|weak| weak.upgrade().is_none()));
+    assert_eq!(
+        observed.get(),
+        Some(Counts {
+            strong: 0,
+            weak: 1,
+            dead: true,
+            uninit: true,
+        })
+    );
+}
+
+#[test]
+fn rc_state_transitions() {
+    use std::cell::Cell;
+
+    #[derive(Default)]
+    struct Counters {
+        strong: Cell<usize>,
+        weak: Cell<usize>,
+    }
+
+    impl RcInnerPtr for Counters {
+        fn weak_ref(&self) -> &Cell<usize> {
+            &self.weak
+        }
+
+        fn strong_ref(&self) -> &Cell<usize> {
+            &self.strong
+        }
+    }
+
+    let counters = Counters {
+        strong: Cell::new(1),
+        weak: Cell::new(1),
+    };
+    assert_eq!(counters.state(), RcState::Live(1));
+    assert!(!counters.is_dead());
+    assert!(!counters.is_uninit());
+
+    counters.inc_strong();
+    assert_eq!(counters.state(), RcState::Live(2));
+
+    counters.dec_strong();
+    counters.dec_strong();
+    assert_eq!(counters.state(), RcState::Dead);
+    assert!(counters.is_dead());
+    assert!(!counters.is_uninit());
+
+    counters.make_uninit();
+    assert_eq!(counters.state(), RcState::Uninit);
+    assert!(counters.is_dead());
+    assert!(counters.is_uninit());
+
+    assert_eq!(Counters::default().state(), RcState::Dead);
+}
+
+#[test]
+fn rc_state_through_weak() {
+    let x = Rc::new(5);
+    let y = Rc::clone(&x);
+    let weak = Rc::downgrade(&x);
+    assert_eq!(weak.inner().unwrap().state(), RcState::Live(2));
+    drop(y);
+    assert_eq!(weak.inner().unwrap().state(), RcState::Live(1));
+    drop(x);
+    assert_eq!(weak.inner().unwrap().state(), RcState::Uninit);
+    assert_eq!(weak.strong_count(), 0);
+    assert_eq!(weak.weak_count(), 0);
+}
+
+#[test]
+fn leak() {
+    let x = Rc::new(String::from("hello"));
+    let weak = Rc::downgrade(&x);
+    let leaked: &'static String = Rc::leak(x);
+    assert_eq!(leaked, "hello");
+    assert_eq!(weak.strong_count(), 1);
+    assert!(weak.upgrade().is_some());
+
+    drop(unsafe { Rc::from_raw(leaked) });
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+#[cfg(feature = "cycle-detection")]
+fn leak_cycle_member() {
+    use std::cell::Cell;
+
+    struct Node {
+        next: RefCell<Option<Rc<Node>>>,
+        drops: Rc<Cell<usize>>,
+        data: i32,
+    }
+
+    impl Drop for Node {
+        fn drop(&mut self) {
+            self.drops.set(self.drops.get() + 1);
+        }
+    }
+
+    let drops = Rc::new(Cell::new(0));
+    let [first, second] = ring(
+        [
+            Node {
+                next: RefCell::new(None),
+                drops: Rc::clone(&drops),
+                data: 1,
+            },
+            Node {
+                next: RefCell::new(None),
+                drops: Rc::clone(&drops),
+                data: 2,
+            },
+        ],
+        |node, next| *node.next.borrow_mut() = Some(next),
+    );
+
+    let leaked = Rc::leak(first);
+    drop(second);
+
+    // The leaked `Rc` is an external owner, so the cycle is not collected.
+    assert_eq!(drops.get(), 0);
+    assert_eq!(leaked.data, 1);
+    assert_eq!(leaked.next.borrow().as_ref().unwrap().data, 2);
+
+    drop(unsafe { Rc::from_raw(leaked) });
+    assert_eq!(drops.get(), 2);
+}
+
+#[test]
+fn new_many() {
+    let mut nodes = Rc::new_many((0..4).map(|idx| format!("node {idx}")));
+    assert_eq!(nodes.len(), 4);
+    for (idx, node) in nodes.iter().enumerate() {
+        assert_eq!(**node, format!("node {idx}"));
+        assert_eq!(Rc::strong_count(node), 1);
+    }
+
+    // Each node is an independent allocation.
+    let last = nodes.pop().unwrap();
+    assert_eq!(Rc::try_unwrap(last).unwrap(), "node 3");
+    drop(nodes.remove(0));
+    assert_eq!(*nodes[0], "node 1");
+    assert!(!Rc::ptr_eq(&nodes[0], &nodes[1]));
+
+    assert!(Rc::<i32>::new_many([]).is_empty());
+}
+
+#[test]
+fn weak_points_to_live() {
+    let five = Rc::new(5);
+    let weak = Rc::downgrade(&five);
+    assert!(weak.points_to(&five));
+    assert!(weak.points_to(&Rc::clone(&five)));
+    assert!(!weak.points_to(&Rc::new(5)));
+    // `points_to` does not upgrade the `Weak`.
+    assert_eq!(Rc::strong_count(&five), 1);
+}
+
+#[test]
+fn weak_points_to_dropped() {
+    let five = Rc::new(5);
+    let weak = Rc::downgrade(&five);
+    drop(five);
+
+    // The `Weak` keeps the allocation alive, so a new `Rc` never aliases it.
+    let six = Rc::new(6);
+    assert!(!weak.points_to(&six));
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn weak_points_to_empty() {
+    let empty = Weak::<i32>::new();
+    let five = Rc::new(5);
+    assert!(!empty.points_to(&five));
+}
+
+#[test]
+fn try_make_mut_unique() {
+    let mut data = Rc::new(5);
+    let weak = Rc::downgrade(&data);
+    *Rc::try_make_mut(&mut data).unwrap() += 1;
+    assert_eq!(*data, 6);
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn try_make_mut_shared_strong() {
+    let mut data = Rc::new(5);
+    let other_data = Rc::clone(&data);
+    *Rc::try_make_mut(&mut data).unwrap() += 1;
+    assert_eq!(*data, 6);
+    assert_eq!(*other_data, 5);
+    assert!(!Rc::ptr_eq(&data, &other_data));
+}
+
+#[test]
+#[cfg(feature = "cycle-detection")]
+fn try_make_mut_cycle_member() {
+    #[derive(Clone)]
+    struct Node {
+        next: RefCell<Option<Rc<Node>>>,
+        data: i32,
+    }
+
+    let nodes = ring(
+        [0, 1, 2].map(|data| Node {
+            next: RefCell::new(None),
+            data,
+        }),
+        |node, next| *node.next.borrow_mut() = Some(next),
+    );
+
+    let mut nodes = nodes.into_iter();
+    let mut first = nodes.next().unwrap();
+    drop(nodes);
+    assert_eq!(Rc::strong_count(&first), 2);
+
+    assert_eq!(
+        Rc::try_make_mut(&mut first).err(),
+        Some(MakeMutError::CycleMember)
+    );
+    assert_eq!(Rc::strong_count(&first), 2);
+    assert_eq!(first.data, 0);
+
+    // With an external owner, the clone is shared and not owned by the cycle.
+    let external = Rc::clone(&first);
+    Rc::try_make_mut(&mut first).unwrap().data = 10;
+    assert_eq!(first.data, 10);
+    assert_eq!(external.data, 0);
+}
+
+#[test]
+fn ptr_key_dedup() {
+    use std::collections::HashSet;
+    use std::vec;
+
+    // `RefCell` is neither `Hash` nor `Eq`.
+    let first = Rc::new(RefCell::new(1));
+    let second = Rc::new(RefCell::new(1));
+    let roots = vec![
+        Rc::clone(&first),
+        Rc::clone(&second),
+        Rc::clone(&first),
+        Rc::clone(&first),
+    ];
+
+    let roots = roots.into_iter().map(PtrKey).collect::<HashSet<_>>();
+    assert_eq!(roots.len(), 2);
+    assert!(roots.contains(&PtrKey(Rc::clone(&first))));
+    assert!(roots.contains(&PtrKey(Rc::clone(&second))));
+    assert!(!roots.contains(&PtrKey(Rc::new(RefCell::new(1)))));
+
+    drop(roots);
+    assert_eq!(Rc::strong_count(&first), 1);
+    assert_eq!(Rc::strong_count(&second), 1);
+}
+
+#[test]
+#[cfg(feature = "cycle-detection")]
+fn adopt_and_keep() {
+    use std::vec::Vec;
+
+    #[derive(Default)]
+    struct Array {
+        buffer: Vec<Rc<RefCell<Self>>>,
+    }
+
+    let array = Rc::new(RefCell::new(Array::default()));
+    for _ in 0..10 {
+        let item = unsafe { Rc::adopt_and_keep(&array, &array) };
+        array.borrow_mut().buffer.push(item);
+    }
+    assert_eq!(Rc::strong_count(&array), 11);
+    assert_eq!(Rc::strong_count_excluding_cycle(&array), 1);
+
+    let weak = Rc::downgrade(&array);
+    drop(array);
+    assert!(weak.upgrade().is_none());
+    assert_eq!(weak.weak_count(), 0);
+}
+
+#[test]
+#[cfg(feature = "cycle-detection")]
+fn adopt_and_keep_increments_counts_once() {
+    let parent = Rc::new(());
+    let child = Rc::new(());
+
+    let kept = unsafe { Rc::adopt_and_keep(&parent, &child) };
+    assert_eq!(Rc::strong_count(&child), 2);
+    assert_eq!(Rc::out_degree(&parent), 1);
+    assert_eq!(Rc::in_degree(&child), 1);
+
+    let again = unsafe { Rc::adopt_and_keep(&parent, &child) };
+    assert_eq!(Rc::strong_count(&child), 3);
+    assert_eq!(Rc::out_degree(&parent), 2);
+    assert_eq!(Rc::in_degree(&child), 2);
+    assert_eq!(Rc::strong_count(&parent), 1);
+
+    Rc::unadopt(&parent, &again);
+    Rc::unadopt(&parent, &kept);
+}
+
+#[test]
+#[cfg(feature = "cycle-detection")]
+fn detach_from_graph_middle_of_ring() {
+    struct Node {
+        next: RefCell<Option<Rc<Node>>>,
+        prev: RefCell<Option<Rc<Node>>>,
+    }
+
+    let nodes = Rc::new_many((0..3).map(|_| Node {
+        next: RefCell::new(None),
+        prev: RefCell::new(None),
+    }));
+    for (idx, node) in nodes.iter().enumerate() {
+        let next = &nodes[(idx + 1) % nodes.len()];
+        let prev = &nodes[(idx + nodes.len() - 1) % nodes.len()];
+        *node.next.borrow_mut() = Some(unsafe { Rc::adopt_and_keep(node, next) });
+        *node.prev.borrow_mut() = Some(unsafe { Rc::adopt_and_keep(node, prev) });
+    }
+    let [first, middle, last] = <[Rc<Node>; 3]>::try_from(nodes).ok().unwrap();
+    assert_eq!(Rc::graph_size(&first), 6);
+
+    // Unlink `middle` and splice its neighbors together.
+    let removed = [
+        first
+            .next
+            .replace(Some(unsafe { Rc::adopt_and_keep(&first, &last) })),
+        last.prev
+            .replace(Some(unsafe { Rc::adopt_and_keep(&last, &first) })),
+        middle.next.take(),
+        middle.prev.take(),
+    ];
+    Rc::detach_from_graph(&middle);
+    drop(removed);
+
+    assert!(!Rc::is_graphed(&middle));
+    assert_eq!(Rc::strong_count(&middle), 1);
+    assert_eq!(Rc::graph_size(&first), 4);
+    assert_eq!(Rc::out_degree(&first), 2);
+    assert_eq!(Rc::in_degree(&last), 2);
+    #[cfg(debug_assertions)]
+    assert_eq!(Rc::validate_graph(&first), Ok(()));
+
+    let weaks = [&first, &last].map(Rc::downgrade);
+    drop(first);
+    drop(last);
+    assert!(weaks.iter().all(|weak| weak.upgrade().is_none()));
+    assert!(Rc::try_unwrap(middle).is_ok());
+}
+
+#[test]
+#[cfg(feature = "cycle-detection")]
+fn try_detach_subgraph_y_shaped() {
+    use std::vec;
+    use std::vec::Vec;
+
+    struct Node {
+        children: RefCell<Vec<Rc<Node>>>,
+    }
+
+    fn link(parent: &Rc<Node>, child: &Rc<Node>) {
+        let child = unsafe { Rc::adopt_and_keep(parent, child) };
+        parent.children.borrow_mut().push(child);
+    }
+
+    // root -> a -> b -> a
+    //          \
+    //           -> c <-> d
+    let [root, a, b, c, d] = [(); 5].map(|()| {
+        Rc::new(Node {
+            children: RefCell::new(vec![]),
+        })
+    });
+    link(&root, &a);
+    link(&a, &b);
+    link(&b, &a);
+    link(&a, &c);
+    link(&c, &d);
+    link(&d, &c);
+    assert!(Rc::same_graph(&root, &d));
+
+    // `b` adopted `a`, so removing `a -> b` does not split the graph.
+    let removed = a.children.borrow_mut().remove(0);
+    assert!(!Rc::try_detach_subgraph(&a, &b));
+    assert!(Rc::same_graph(&root, &b));
+    a.children.borrow_mut().insert(0, removed);
+    unsafe {
+        Rc::adopt_unchecked(&a, &b);
+    }
+
+    let removed = a.children.borrow_mut().pop().unwrap();
+    assert!(Rc::try_detach_subgraph(&a, &c));
+    drop(removed);
+    assert!(!Rc::same_graph(&root, &c));
+    assert!(Rc::same_graph(&c, &d));
+    assert!(!Rc::try_detach_subgraph(&a, &c));
+    #[cfg(debug_assertions)]
+    assert_eq!(Rc::validate_graph(&root), Ok(()));
+
+    // The detached branch is collected on its own.
+    let branch = [&c, &d].map(Rc::downgrade);
+    drop(c);
+    drop(d);
+    assert!(branch.iter().all(|weak| weak.upgrade().is_none()));
+    assert_eq!(Rc::graph_nodes(&root).len(), 3);
+
+    let trunk = [&root, &a, &b].map(Rc::downgrade);
+    drop(a);
+    drop(b);
+    assert!(trunk.iter().all(|weak| weak.upgrade().is_some()));
+    drop(root);
+    assert!(trunk.iter().all(|weak| weak.upgrade().is_none()));
+}
+
+#[test]
+#[cfg(feature = "cycle-detection")]
+fn try_unadopt() {
+    let first = Rc::new(1);
+    let second = Rc::new(2);
+    assert!(!Rc::try_unadopt(&first, &second));
+
+    let clone = Rc::clone(&second);
+    unsafe {
+        Rc::adopt_unchecked(&first, &clone);
+    }
+    assert!(Rc::try_unadopt(&first, &clone));
+    assert!(!Rc::try_unadopt(&first, &clone));
+    assert!(!first.inner().has_links());
+    assert!(!second.inner().has_links());
+
+    unsafe {
+        Rc::adopt_unchecked(&first, &first);
+    }
+    assert!(Rc::try_unadopt(&first, &first));
+    assert!(!Rc::try_unadopt(&first, &first));
+}
+
+#[test]
+#[cfg(feature = "cycle-detection")]
+fn cycle_len() {
+    struct Node {
+        next: RefCell<Option<Rc<Node>>>,
+    }
+
+    let nodes = ring(
+        [(); 10].map(|()| Node {
+            next: RefCell::new(None),
+        }),
+        |node, next| *node.next.borrow_mut() = Some(next),
+    );
+    // Every member of the ring is owned by `nodes`.
+    assert_eq!(Rc::cycle_len(&nodes[0]), None);
+
+    let mut nodes = nodes.into_iter();
+    let first = nodes.next().unwrap();
+    let external = nodes.next_back().unwrap();
+    drop(nodes);
+    assert_eq!(Rc::cycle_len(&first), None);
+    assert_eq!(Rc::cycle_len(&external), None);
+
+    drop(external);
+    assert_eq!(Rc::cycle_len(&first), Some(10));
+    assert_eq!(Rc::strong_count(&first), 2);
+}
+
+#[test]
+#[cfg(feature = "cycle-detection")]
+fn cycle_len_acyclic_tree() {
+    let root = Rc::new(());
+    let left = Rc::new(());
+    let right = Rc::new(());
+    let children = unsafe {
+        [
+            Rc::adopt_and_keep(&root, &left),
+            Rc::adopt_and_keep(&root, &right),
+        ]
+    };
+    drop(left);
+    drop(right);
+
+    // `root` is the only external owner of the tree, but is not in a cycle.
+    assert_eq!(Rc::cycle_len(&root), None);
+    assert!(children.iter().all(|child| Rc::cycle_len(child).is_none()));
+    for child in &children {
+        Rc::unadopt(&root, child);
+    }
+}
+
+#[test]
+#[cfg(feature = "cycle-detection")]
+fn cycle_len_acyclic_chain() {
+    let first = Rc::new(());
+    let second = Rc::new(());
+    let third = Rc::new(());
+    let second_owned = unsafe { Rc::adopt_and_keep(&first, &second) };
+    let third_owned = unsafe { Rc::adopt_and_keep(&second, &third) };
+    drop(second);
+    drop(third);
+
+    assert_eq!(Rc::cycle_len(&first), None);
+    assert_eq!(Rc::cycle_len(&second_owned), None);
+    assert_eq!(Rc::cycle_len(&third_owned), None);
+    Rc::unadopt(&second_owned, &third_owned);
+    Rc::unadopt(&first, &second_owned);
+}
+
+#[test]
+#[cfg(feature = "cycle-detection")]
+fn adopt_via_weak() {
+    struct Node {
+        next: RefCell<Option<Rc<Node>>>,
+    }
+
+    let first = Rc::new(Node {
+        next: RefCell::new(None),
+    });
+    let second = Rc::new(Node {
+        next: RefCell::new(None),
+    });
+
+    let next = unsafe { Rc::adopt_via_weak(&first, &Rc::downgrade(&second)) };
+    assert!(next.as_ref().is_some_and(|next| Rc::ptr_eq(next, &second)));
+    *first.next.borrow_mut() = next;
+    let next = unsafe { Rc::adopt_via_weak(&second, &Rc::downgrade(&first)) };
+    *second.next.borrow_mut() = next;
+    assert_eq!(Rc::strong_count(&first), 2);
+    assert_eq!(Rc::strong_count(&second), 2);
+
+    let collected = Rc::downgrade(&first);
+    drop(first);
+    drop(second);
+    assert!(collected.upgrade().is_none());
+
+    let third = Rc::new(Node {
+        next: RefCell::new(None),
+    });
+    assert!(unsafe { Rc::adopt_via_weak(&third, &collected) }.is_none());
+    assert!(unsafe { Rc::adopt_via_weak(&third, &Weak::new()) }.is_none());
+    assert!(!third.inner().has_links());
+}
+
+#[test]
+#[cfg(feature = "cycle-detection")]
+fn debug_graph_ring() {
+    struct Node {
+        next: Option<Rc<RefCell<Node>>>,
+        data: i32,
+    }
+
+    let left = Node {
+        next: None,
+        data: 123,
+    };
+    let left = Rc::new(RefCell::new(left));
+    let right = Node {
+        next: Some(Rc::clone(&left)),
+        data: 456,
+    };
+    let right = Rc::new(RefCell::new(right));
+    unsafe {
+        Rc::adopt_unchecked(&right, &left);
+    }
+    left.borrow_mut().next = Some(Rc::clone(&right));
+    unsafe {
+        Rc::adopt_unchecked(&left, &right);
+    }
+    assert_eq!(left.borrow().data + right.borrow().data, 579);
+
+    let left_ptr = format!("{left:p}");
+    let right_ptr = format!("{right:p}");
+    let debug = format!("{:?}", Rc::debug_graph(&left));
+    assert_eq!(
+        debug,
+        format!("Rc {{ ptr: {left_ptr}, strong: 2, weak: 0, forward: [{right_ptr}], backward: [{right_ptr}] }}")
+    );
+    let debug = format!("{:?}", Rc::debug_graph(&right));
+    assert!(debug.contains(&left_ptr));
+    assert!(debug.contains(&right_ptr));
+}
+
+#[test]
+fn debug_graph_no_links() {
+    let five = Rc::new(5);
+    let _weak = Rc::downgrade(&five);
+    let debug = format!("{:?}", Rc::debug_graph(&five));
+    #[cfg(feature = "cycle-detection")]
+    assert_eq!(
+        debug,
+        format!("Rc {{ ptr: {five:p}, strong: 1, weak: 1, forward: [], backward: [] }}")
+    );
+    #[cfg(not(feature = "cycle-detection"))]
+    assert_eq!(debug, format!("Rc {{ ptr: {five:p}, strong: 1, weak: 1 }}"));
+}
+
+#[test]
+fn weak_ref() {
+    let five = Rc::new(5);
+    let weak = Rc::downgrade(&five);
+    assert_eq!(Rc::weak_count(&five), 1);
+
+    let weak_ref = Rc::weak_ref(&five);
+    let copy = weak_ref;
+    assert_eq!(Rc::weak_count(&five), 1);
+    assert_eq!(weak_ref.weak_count(), 1);
+    assert_eq!(copy.strong_count(), 1);
+
+    let upgraded = weak_ref.upgrade().unwrap();
+    assert!(Rc::ptr_eq(&upgraded, &five));
+    assert_eq!(Rc::strong_count(&five), 2);
+    drop(upgraded);
+
+    let owned = weak_ref.to_weak();
+    assert_eq!(Rc::weak_count(&five), 2);
+    assert!(owned.ptr_eq(&weak));
+    drop(owned);
+
+    assert_eq!(Rc::weak_count(&five), 1);
+    assert_eq!(Rc::strong_count(&five), 1);
+    drop(five);
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn try_clone_overflow() {
+    let five = Rc::new(5);
+    let also_five = Rc::try_clone(&five).unwrap();
+    assert_eq!(Rc::strong_count(&five), 2);
+    drop(also_five);
+
+    // Incrementing the strong count to the limit one clone at a time is not
+    // feasible in a test, so set the count directly.
+    let strong = usize::MAX - 2;
+    five.inner().strong_ref().set(strong);
+    let at_limit = Rc::try_clone(&five).unwrap();
+    assert_eq!(Rc::strong_count(&five), usize::MAX - 1);
+    assert_eq!(Rc::try_clone(&five).unwrap_err(), RefCountOverflow);
+    assert_eq!(Rc::strong_count(&five), usize::MAX - 1);
+
+    drop(at_limit);
+    five.inner().strong_ref().set(1);
+}
+
+#[test]
+#[cfg(feature = "cycle-detection")]
+fn in_and_out_degree() {
+    use std::vec::Vec;
+
+    struct Node {
+        links: RefCell<Vec<Rc<Node>>>,
+    }
+
+    let nodes = (0..4)
+        .map(|_| {
+            Rc::new(Node {
+                links: RefCell::new(Vec::new()),
+            })
+        })
+        .collect::<Vec<_>>();
+    for node in &nodes {
+        assert_eq!(Rc::in_degree(node), 0);
+        assert_eq!(Rc::out_degree(node), 0);
+    }
+    for left in &nodes {
+        for right in &nodes {
+            let link = Rc::clone(right);
+            unsafe {
+                Rc::adopt_unchecked(left, &link);
+            }
+            left.links.borrow_mut().push(link);
+        }
+    }
+    for node in &nodes {
+        assert_eq!(Rc::in_degree(node), 4);
+        assert_eq!(Rc::out_degree(node), 4);
+    }
+
+    let weak = Rc::downgrade(&nodes[0]);
+    drop(nodes);
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+#[cfg(feature = "cycle-detection")]
+fn is_graphed_and_graph_size() {
+    let parent = Rc::new(1);
+    let child = Rc::new(2);
+    assert!(!Rc::is_graphed(&parent));
+    assert!(!Rc::is_graphed(&child));
+    assert_eq!(Rc::graph_size(&parent), 0);
+
+    let clone = Rc::clone(&child);
+    unsafe {
+        Rc::adopt_unchecked(&parent, &clone);
+        Rc::adopt_unchecked(&parent, &clone);
+    }
+    assert!(Rc::is_graphed(&parent));
+    assert!(Rc::is_graphed(&child));
+    assert_eq!(Rc::graph_size(&parent), 2);
+    assert_eq!(Rc::graph_size(&child), 2);
+
+    Rc::unadopt(&parent, &clone);
+    assert!(Rc::is_graphed(&parent));
+    assert_eq!(Rc::graph_size(&child), 1);
+
+    Rc::unadopt(&parent, &clone);
+    assert!(!Rc::is_graphed(&parent));
+    assert!(!Rc::is_graphed(&child));
+    assert_eq!(Rc::graph_size(&parent), 0);
+}
+
+#[test]
+#[cfg(all(feature = "std", feature = "cycle-detection"))]
+fn try_adopt_max_graph_size() {
+    use crate::{max_graph_size, set_max_graph_size, GraphTooLarge};
+
+    assert_eq!(max_graph_size(), usize::MAX);
+    set_max_graph_size(3);
+
+    let first = Rc::new(1);
+    let second = Rc::new(2);
+    let third = Rc::new(3);
+    let fourth = Rc::new(4);
+    let second_clone = Rc::clone(&second);
+    let third_clone = Rc::clone(&third);
+    let fourth_clone = Rc::clone(&fourth);
+    let first_clone = Rc::clone(&first);
+    unsafe {
+        // Two disjoint graphs with one edge each.
+        assert_eq!(Rc::try_adopt(&first, &second_clone), Ok(()));
+        assert_eq!(Rc::try_adopt(&third, &fourth_clone), Ok(()));
+        // Joining them reaches the limit.
+        assert_eq!(Rc::try_adopt(&second, &third_clone), Ok(()));
+        assert_eq!(Rc::graph_size(&first), 3);
+        // One more edge would exceed it.
+        assert_eq!(
+            Rc::try_adopt(&fourth, &first_clone),
+            Err(GraphTooLarge { limit: 3 })
+        );
+    }
+    assert_eq!(Rc::graph_size(&first), 3);
+    assert_eq!(Rc::in_degree(&first), 0);
+
+    Rc::unadopt(&third, &fourth_clone);
+    unsafe {
+        assert_eq!(Rc::try_adopt(&fourth, &first_clone), Ok(()));
+    }
+    Rc::unadopt(&fourth, &first_clone);
+    Rc::unadopt(&second, &third_clone);
+    Rc::unadopt(&first, &second_clone);
+
+    set_max_graph_size(usize::MAX);
+}
+
+#[test]
+fn try_swap_unique() {
+    let mut first = Rc::new(String::from("first"));
+    let mut second = Rc::new(String::from("second"));
+    assert!(Rc::try_swap(&mut first, &mut second));
+    assert_eq!(*first, "second");
+    assert_eq!(*second, "first");
+}
+
+#[test]
+fn try_swap_shared() {
+    let mut first = Rc::new(String::from("first"));
+    let mut second = Rc::new(String::from("second"));
+
+    let clone = Rc::clone(&second);
+    assert!(!Rc::try_swap(&mut first, &mut second));
+    assert!(!Rc::try_swap(&mut second, &mut first));
+    drop(clone);
+
+    let weak = Rc::downgrade(&first);
+    assert!(!Rc::try_swap(&mut first, &mut second));
+    drop(weak);
+
+    assert_eq!(*first, "first");
+    assert_eq!(*second, "second");
+    assert!(Rc::try_swap(&mut first, &mut second));
+}
+
+#[test]
+#[cfg(feature = "cycle-detection")]
+fn try_swap_graphed() {
+    struct Node {
+        child: Option<Rc<Node>>,
+    }
+
+    let child = Rc::new(Node { child: None });
+    let clone = Rc::clone(&child);
+    let mut other = Rc::new(Node { child: None });
+    let mut parent = Rc::new(Node { child: Some(clone) });
+    unsafe {
+        Rc::adopt_unchecked(&parent, parent.child.as_ref().unwrap());
+    }
+
+    // `parent` is unique, but its adoption of `child` would not follow the
+    // swapped value into `other`.
+    assert!(!Rc::try_swap(&mut parent, &mut other));
+    assert!(parent.child.is_some());
+    assert!(other.child.is_none());
+}
+
+#[test]
+fn replace_unique() {
+    let mut rc = Rc::new(String::from("old"));
+    assert_eq!(
+        Rc::replace(&mut rc, String::from("new")).as_deref(),
+        Some("old")
+    );
+    assert_eq!(*rc, "new");
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn replace_shared() {
+    let mut rc = Rc::new(String::from("old"));
+
+    let clone = Rc::clone(&rc);
+    assert_eq!(Rc::replace(&mut rc, String::from("new")), None);
+    drop(clone);
+
+    let weak = Rc::downgrade(&rc);
+    assert_eq!(Rc::replace(&mut rc, String::from("new")), None);
+    drop(weak);
+
+    assert_eq!(*rc, "old");
+}
+
+#[test]
+fn take_unique() {
+    let mut rc = Rc::new(String::from("value"));
+    assert_eq!(Rc::take(&mut rc).as_deref(), Some("value"));
+    assert_eq!(*rc, "");
+}
+
+#[test]
+fn take_shared() {
+    let mut rc = Rc::new(String::from("value"));
+
+    let clone = Rc::clone(&rc);
+    assert_eq!(Rc::take(&mut rc), None);
+    drop(clone);
+
+    let weak = Rc::downgrade(&rc);
+    assert_eq!(Rc::take(&mut rc), None);
+    drop(weak);
+
+    assert_eq!(*rc, "value");
+}
+
+#[test]
+#[cfg(feature = "cycle-detection")]
+fn graph_heap_size_ring() {
+    use crate::link::Links;
+
+    struct Node {
+        _payload: [u8; 100],
+        next: RefCell<Option<Rc<Node>>>,
+    }
+
+    impl Node {
+        fn new() -> Self {
+            Self {
+                _payload: [0; 100],
+                next: RefCell::new(None),
+            }
+        }
+    }
+
+    const LEN: usize = 8;
+    let rcbox = size_of::<RcBox<Node>>();
+    assert_eq!(Rc::graph_heap_size(&Rc::new(Node::new())), rcbox);
+
+    let nodes = ring([(); LEN].map(|()| Node::new()), |node, next| {
+        *node.next.borrow_mut() = Some(next);
+    });
+
+    // Every node has a forward and a backward link.
+    let links = size_of::<RefCell<Links<Node>>>();
+    let min = LEN * (rcbox + links + 2 * size_of::<(usize, usize)>());
+    let size = Rc::graph_heap_size(&nodes[3]);
+    assert!(size >= min, "{size} < {min}");
+    assert!(size <= min + LEN * 256, "{size} is too large");
+    assert!(nodes.iter().all(|node| Rc::graph_heap_size(node) == size));
+}
+
+#[test]
+#[cfg(feature = "cycle-detection")]
+fn new_graphed_with_capacity() {
+    use std::vec::Vec;
+
+    let head = Rc::new_graphed_with_capacity(0, 32);
+    let capacity = head.inner().links().unwrap().borrow().capacity();
+    assert!(capacity >= 32);
+    assert!(!Rc::is_graphed(&head));
+
+    let nodes = Rc::new_many(1..=32);
+    for node in &nodes {
+        unsafe {
+            Rc::adopt_unchecked(&head, node);
+        }
+    }
+    assert_eq!(head.inner().links().unwrap().borrow().capacity(), capacity);
+    assert_eq!(Rc::out_degree(&head), 32);
+
+    for node in &nodes {
+        Rc::unadopt(&head, node);
+    }
+    let weaks = nodes.iter().map(Rc::downgrade).collect::<Vec<_>>();
+    drop(nodes);
+    assert!(weaks.iter().all(|weak| weak.upgrade().is_none()));
+}
+
+#[test]
+#[cfg(feature = "cycle-detection")]
+fn reserve_adoptions() {
+    let head = Rc::new(0);
+    assert!(head.inner().links().is_none());
+    Rc::reserve_adoptions(&head, 64);
+    let capacity = head.inner().links().unwrap().borrow().capacity();
+    assert!(capacity >= 64);
+    assert!(!Rc::is_graphed(&head));
+
+    let nodes = Rc::new_many(1..=64);
+    for node in &nodes {
+        unsafe {
+            Rc::adopt_unchecked(&head, node);
+        }
+    }
+    assert_eq!(head.inner().links().unwrap().borrow().capacity(), capacity);
+    assert_eq!(Rc::out_degree(&head), 64);
+
+    for node in &nodes {
+        Rc::unadopt(&head, node);
+    }
+    assert!(!Rc::is_graphed(&head));
+}
+
+#[test]
+#[cfg(feature = "cycle-detection")]
+fn shrink_graph_after_unadopt() {
+    let head = Rc::new(0);
+    let nodes = Rc::new_many(1..=100);
+    for node in &nodes {
+        unsafe {
+            Rc::adopt_unchecked(&head, node);
+        }
+    }
+    let full = Rc::graph_capacity(&head);
+    let head_capacity = head.inner().links().unwrap().borrow().capacity();
+    assert!(head_capacity >= 100);
+    assert!(full >= 200);
+
+    for node in &nodes[10..] {
+        Rc::unadopt(&head, node);
+    }
+    let before = Rc::graph_capacity(&head);
+
+    Rc::shrink_graph(&nodes[3]);
+    let after = Rc::graph_capacity(&head);
+    assert!(after < before, "{after} >= {before}");
+    assert!(head.inner().links().unwrap().borrow().capacity() < head_capacity);
+    assert!(after >= 20);
+    assert_eq!(Rc::out_degree(&head), 10);
+
+    // The graph keeps working after shrinking.
+    for node in &nodes[10..20] {
+        unsafe {
+            Rc::adopt_unchecked(&head, node);
+        }
+    }
+    assert_eq!(Rc::out_degree(&head), 20);
+    for node in &nodes[..20] {
+        Rc::unadopt(&head, node);
+    }
+    assert!(!Rc::is_graphed(&head));
+}
+
+#[test]
+fn ptr_cmp_sort_is_stable() {
+    use core::cmp::Ordering;
+    use std::vec::Vec;
+
+    let nodes = Rc::new_many(0..16);
+    let mut sorted = nodes.clone();
+    sorted.sort_by(Rc::ptr_cmp);
+    let mut reversed = nodes.iter().rev().cloned().collect::<Vec<_>>();
+    reversed.sort_by(Rc::ptr_cmp);
+
+    assert!(sorted
+        .iter()
+        .zip(&reversed)
+        .all(|(left, right)| Rc::ptr_eq(left, right)));
+    assert!(sorted
+        .windows(2)
+        .all(|pair| Rc::ptr_cmp(&pair[0], &pair[1]) == Ordering::Less));
+
+    let clone = Rc::clone(&nodes[0]);
+    assert_eq!(Rc::ptr_cmp(&nodes[0], &clone), Ordering::Equal);
+}
+
+#[test]
+fn ptr_hash_identity() {
+    use core::hash::{BuildHasher, BuildHasherDefault};
+    use std::collections::hash_map::DefaultHasher;
+
+    let hasher = BuildHasherDefault::<DefaultHasher>::default();
+    let hash = |rc: &Rc<i32>| {
+        let mut state = hasher.build_hasher();
+        Rc::ptr_hash(rc, &mut state);
+        core::hash::Hasher::finish(&state)
+    };
+
+    let five = Rc::new(5);
+    let same_five = Rc::clone(&five);
+    let other_five = Rc::new(5);
+    assert_eq!(hash(&five), hash(&same_five));
+    assert_ne!(hash(&five), hash(&other_five));
+}
+
+#[test]
+fn weak_count_tracks_downgrades() {
+    use std::vec::Vec;
+
+    let five = Rc::new(5);
+    let mut weaks = Vec::new();
+    for expected in 1..=4 {
+        weaks.push(Rc::downgrade(&five));
+        assert_eq!(Rc::weak_count(&five), expected);
+        assert_eq!(Rc::weak_count_detailed(&five).total, expected + 1);
+    }
+    weaks.push(weaks[0].clone());
+    assert_eq!(Rc::weak_count(&five), 5);
+
+    // Borrowed weak references and `Weak::new` do not count.
+    let _weak_ref = Rc::weak_ref(&five);
+    let _dangling = Weak::<i32>::new();
+    let _clone = Rc::clone(&five);
+    assert_eq!(Rc::weak_count(&five), 5);
+
+    while let Some(weak) = weaks.pop() {
+        drop(weak);
+        assert_eq!(Rc::weak_count(&five), weaks.len());
+    }
+    assert_eq!(Rc::weak_count_detailed(&five).total, 1);
+}
+
+#[test]
+fn rc_count_methods() {
+    let five = Rc::new(5);
+    assert_eq!(five.rc_strong_count(), Rc::strong_count(&five));
+    assert_eq!(five.rc_weak_count(), Rc::weak_count(&five));
+
+    let clone = Rc::clone(&five);
+    let weak = Rc::downgrade(&five);
+    assert_eq!(five.rc_strong_count(), 2);
+    assert_eq!(five.rc_strong_count(), Rc::strong_count(&clone));
+    assert_eq!(five.rc_weak_count(), 1);
+    assert_eq!(clone.rc_weak_count(), Rc::weak_count(&five));
+
+    drop(weak);
+    assert_eq!(five.rc_weak_count(), 0);
+}
+
+#[test]
+#[cfg(feature = "cycle-detection")]
+fn extend_adopting_self_referential_array() {
+    use std::vec::Vec;
+
+    #[derive(Default)]
+    struct Array {
+        buffer: Vec<Rc<RefCell<Self>>>,
+    }
+
+    let array = Rc::new(RefCell::new(Array::default()));
+    let items = (0..10).map(|_| Rc::clone(&array)).collect::<Vec<_>>();
+    array.borrow_mut().buffer.extend(items);
+    unsafe {
+        Rc::extend_adopting(&array, &array.borrow().buffer);
+    }
+    assert_eq!(Rc::strong_count(&array), 11);
+    assert_eq!(Rc::strong_count_excluding_cycle(&array), 1);
+
+    let weak = Rc::downgrade(&array);
+    drop(array);
+    assert!(weak.upgrade().is_none());
+    assert_eq!(weak.weak_count(), 0);
+}
+
+#[test]
+#[cfg(feature = "cycle-detection")]
+fn reachable_from_any() {
+    use std::vec::Vec;
+
+    // Two disjoint chains: 0 -> 1 -> 2 and 3 -> 4.
+    let nodes = Rc::new_many(0..5);
+    let mut clones = Vec::new();
+    for (parent, child) in [(0, 1), (1, 2), (3, 4)] {
+        let clone = Rc::clone(&nodes[child]);
+        unsafe {
+            Rc::adopt_unchecked(&nodes[parent], &clone);
+        }
+        clones.push(clone);
+    }
+
+    let first_root = [Rc::clone(&nodes[0])];
+    let second_root = [Rc::clone(&nodes[3])];
+    assert!(Rc::reachable_from_any(&nodes[0], &first_root));
+    assert!(Rc::reachable_from_any(&nodes[2], &first_root));
+    assert!(!Rc::reachable_from_any(&nodes[4], &first_root));
+    assert!(Rc::reachable_from_any(&nodes[4], &second_root));
+    assert!(!Rc::reachable_from_any(&nodes[2], &second_root));
+
+    // Adoptions are not followed backward.
+    assert!(!Rc::reachable_from_any(&nodes[0], &[Rc::clone(&nodes[2])]));
+
+    let both = [Rc::clone(&nodes[0]), Rc::clone(&nodes[3])];
+    assert!(nodes.iter().all(|node| Rc::reachable_from_any(node, &both)));
+    assert!(!Rc::reachable_from_any(&nodes[0], &[]));
+}
+
+#[test]
+#[cfg(feature = "cycle-detection")]
+fn same_graph_after_merge() {
+    use std::vec::Vec;
+
+    // Two disjoint graphs: 0 -> 1 and 2 -> 3.
+    let nodes = Rc::new_many(0..5);
+    let mut clones = Vec::new();
+    for (parent, child) in [(0, 1), (2, 3)] {
+        let clone = Rc::clone(&nodes[child]);
+        unsafe {
+            Rc::adopt_unchecked(&nodes[parent], &clone);
+        }
+        clones.push(clone);
+    }
+    assert!(Rc::same_graph(&nodes[0], &nodes[1]));
+    assert!(Rc::same_graph(&nodes[3], &nodes[2]));
+    assert!(!Rc::same_graph(&nodes[1], &nodes[3]));
+    assert!(!Rc::same_graph(&nodes[0], &nodes[4]));
+    assert!(Rc::same_graph(&nodes[4], &Rc::clone(&nodes[4])));
+
+    // Adopting 2 from 1 merges the graphs.
+    let bridge = Rc::clone(&nodes[2]);
+    unsafe {
+        Rc::adopt_unchecked(&nodes[1], &bridge);
+    }
+    assert!(Rc::same_graph(&nodes[1], &nodes[3]));
+    assert!(Rc::same_graph(&nodes[3], &nodes[0]));
+    assert!(!Rc::same_graph(&nodes[3], &nodes[4]));
+
+    Rc::unadopt(&nodes[1], &bridge);
+    assert!(!Rc::same_graph(&nodes[1], &nodes[3]));
+}
+
+#[test]
+fn try_new_cyclic() {
+    struct Gadget {
+        me: Weak<Gadget>,
+    }
+
+    let gadget = Rc::try_new_cyclic(|me| {
+        assert!(me.upgrade().is_none());
+        assert_eq!(me.strong_count(), 0);
+        Gadget { me: me.clone() }
+    })
+    .unwrap();
+    assert_eq!(Rc::strong_count(&gadget), 1);
+    assert_eq!(Rc::weak_count(&gadget), 1);
+    assert!(Rc::ptr_eq(&gadget, &gadget.me.upgrade().unwrap()));
+
+    let weak = Rc::downgrade(&gadget);
+    drop(gadget);
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn try_new_cyclic_weak_count() {
+    use std::vec;
+    use std::vec::Vec;
+
+    struct Gadget {
+        me: Vec<Weak<Gadget>>,
+    }
+
+    // The weak reference passed to `data_fn` is not counted once the `Rc` is
+    // constructed.
+    let gadget = Rc::try_new_cyclic(|_| Gadget { me: Vec::new() }).unwrap();
+    assert_eq!(Rc::strong_count(&gadget), 1);
+    assert_eq!(Rc::weak_count(&gadget), 0);
+    assert!(Rc::is_unique(&gadget));
+    drop(gadget);
+
+    let gadget = Rc::try_new_cyclic(|me| {
+        let clone = me.clone();
+        assert_eq!(me.weak_count(), 0);
+        drop(clone);
+        Gadget { me: Vec::new() }
+    })
+    .unwrap();
+    assert_eq!(Rc::weak_count(&gadget), 0);
+    drop(gadget);
+
+    let gadget = Rc::try_new_cyclic(|me| Gadget {
+        me: vec![me.clone(), me.clone()],
+    })
+    .unwrap();
+    assert_eq!(Rc::strong_count(&gadget), 1);
+    assert_eq!(Rc::weak_count(&gadget), 2);
+    assert!(gadget
+        .me
+        .iter()
+        .all(|me| Rc::ptr_eq(&me.upgrade().unwrap(), &gadget)));
+
+    let weak = Rc::downgrade(&gadget);
+    assert_eq!(Rc::weak_count(&gadget), 3);
+    drop(gadget);
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn try_new_cyclic_alloc_error() {
+    use core::alloc::AllocError;
+    use core::cell::Cell;
+
+    let called = Cell::new(false);
+    let result = Rc::<i32>::try_new_cyclic_with(
+        |_| {
+            called.set(true);
+            5
+        },
+        |_| Err(AllocError),
+    );
+    assert!(matches!(result, Err(AllocError)));
+    assert!(!called.get());
+}
+
+#[test]
+fn try_new_cyclic_panic() {
+    use std::panic::{self, AssertUnwindSafe};
+
+    let stash = RefCell::new(None);
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        Rc::<String>::try_new_cyclic(|me| {
+            *stash.borrow_mut() = Some(me.clone());
+            panic!("data_fn panicked");
+        })
+    }));
+    assert!(result.is_err());
+
+    // The allocation is kept alive by the stashed weak pointer, but never
+    // holds a value.
+    let weak = stash.borrow_mut().take().unwrap();
+    assert!(weak.upgrade().is_none());
+    assert_eq!(weak.strong_count(), 0);
+    drop(weak);
+}
+
+#[test]
+fn pin_cyclic() {
+    use core::marker::PhantomPinned;
+    use core::pin::Pin;
+
+    struct Node {
+        value: i32,
+        me: Weak<Node>,
+        _pin: PhantomPinned,
+    }
+
+    let node: Pin<Rc<Node>> = unsafe {
+        Rc::pin_cyclic(|me| {
+            assert!(me.upgrade().is_none());
+            Node {
+                value: 7,
+                me: me.clone(),
+                _pin: PhantomPinned,
+            }
+        })
+    };
+    assert_eq!(node.value, 7);
+    let me = node.me.upgrade().unwrap();
+    assert!(core::ptr::eq(&*node, &*me));
+    assert_eq!(Rc::strong_count(&me), 2);
+    drop(me);
+
+    let weak = Weak::clone(&node.me);
+    drop(node);
+    assert!(weak.upgrade().is_none());
+    assert_eq!(weak.strong_count(), 0);
+}
+
+#[test]
+#[cfg(all(debug_assertions, feature = "cycle-detection"))]
+fn validate_graph_detects_corrupt_links() {
+    use crate::link::Link;
+    use crate::GraphInvariantViolation;
+
+    let a = Rc::new(());
+    let b = Rc::new(());
+    let a_addr = Rc::as_ptr(&a) as usize;
+    let b_addr = Rc::as_ptr(&b) as usize;
+    let clone = Rc::clone(&b);
+    unsafe {
+        Rc::adopt_unchecked(&a, &clone);
+    }
+    assert_eq!(Rc::validate_graph(&a), Ok(()));
+    assert_eq!(Rc::validate_graph(&b), Ok(()));
+
+    // Drop the back reference of the adoption.
+    let b_links = b.inner().links().unwrap();
+    b_links.borrow_mut().remove(Link::backward(a.ptr), 1);
+    assert_eq!(
+        Rc::validate_graph(&a),
+        Err(GraphInvariantViolation::UnmatchedForwardLink {
+            from: a_addr,
+            to: b_addr
+        })
+    );
+    b_links.borrow_mut().insert(Link::backward(a.ptr));
+    assert_eq!(Rc::validate_graph(&a), Ok(()));
+
+    // Record a back reference without an adoption.
+    let a_links = a.inner().links().unwrap();
+    a_links.borrow_mut().insert(Link::backward(b.ptr));
+    assert_eq!(
+        Rc::validate_graph(&a),
+        Err(GraphInvariantViolation::UnmatchedBackwardLink {
+            from: b_addr,
+            to: a_addr
+        })
+    );
+    a_links.borrow_mut().remove(Link::backward(b.ptr), 1);
+    assert_eq!(Rc::validate_graph(&a), Ok(()));
+
+    // Mark the adopted node dead.
+    let strong = Rc::strong_count(&b);
+    b.inner().strong_ref().set(0);
+    assert_eq!(
+        Rc::validate_graph(&a),
+        Err(GraphInvariantViolation::DeadLink {
+            from: a_addr,
+            to: b_addr
+        })
+    );
+    b.inner().strong_ref().set(strong);
+    assert_eq!(Rc::validate_graph(&a), Ok(()));
+
+    // Adopt without a matching strong reference.
+    unsafe {
+        Rc::adopt_unchecked(&a, &b);
+        Rc::adopt_unchecked(&a, &b);
+    }
+    assert_eq!(
+        Rc::validate_graph(&b),
+        Err(GraphInvariantViolation::AdoptionsExceedStrongCount {
+            node: b_addr,
+            adoptions: 3,
+            strong: 2
+        })
+    );
+    Rc::unadopt(&a, &b);
+    Rc::unadopt(&a, &b);
+    assert_eq!(Rc::validate_graph(&b), Ok(()));
+
+    Rc::unadopt(&a, &clone);
+}
+
+// A node which stores the `Rc`s it adopts.
+#[cfg(all(debug_assertions, feature = "cycle-detection"))]
+struct AcyclicNode {
+    children: RefCell<std::vec::Vec<Rc<AcyclicNode>>>,
+}
+
+#[cfg(all(debug_assertions, feature = "cycle-detection"))]
+fn acyclic_nodes(len: usize, edges: &[(usize, usize)]) -> std::vec::Vec<Rc<AcyclicNode>> {
+    use std::vec::Vec;
+
+    let nodes = Rc::new_many((0..len).map(|_| AcyclicNode {
+        children: RefCell::new(Vec::new()),
+    }));
+    for &(parent, child) in edges {
+        let child = unsafe { Rc::adopt_and_keep(&nodes[parent], &nodes[child]) };
+        nodes[parent].children.borrow_mut().push(child);
+    }
+    nodes
+}
+
+#[test]
+#[cfg(all(debug_assertions, feature = "cycle-detection"))]
+fn assert_acyclic_diamond() {
+    // 0 -> 1 -> 3
+    //   -> 2 -> 3
+    let nodes = acyclic_nodes(4, &[(0, 1), (0, 2), (1, 3), (2, 3)]);
+    for node in &nodes {
+        Rc::assert_acyclic(node);
+    }
+    Rc::assert_acyclic(&Rc::new(4));
+}
+
+#[test]
+#[cfg(all(debug_assertions, feature = "cycle-detection"))]
+#[should_panic(expected = "cactusref object graph has a cycle")]
+fn assert_acyclic_detects_back_edge() {
+    // 0 -> 1 -> 2 -> 3 -> 1
+    let nodes = acyclic_nodes(4, &[(0, 1), (1, 2), (2, 3), (3, 1)]);
+    Rc::assert_acyclic(&nodes[0]);
+}
+
+#[test]
+#[cfg(all(debug_assertions, feature = "cycle-detection"))]
+#[should_panic(expected = "cactusref object graph has a cycle")]
+fn assert_acyclic_detects_self_adoption() {
+    let nodes = acyclic_nodes(2, &[(0, 1), (1, 1)]);
+    Rc::assert_acyclic(&nodes[0]);
+}
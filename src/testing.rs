@@ -0,0 +1,389 @@
+//! Utilities for asserting that object graphs are deallocated.
+//!
+//! Bookkeeping bugs in code which calls [`Adopt::adopt_unchecked`] and
+//! [`Adopt::unadopt`] may leak orphaned cycles. This module provides a
+//! [`CountingAllocator`] which tracks the bytes allocated on each thread and a
+//! [`LeakDetector`] which asserts that a closure frees everything it
+//! allocates.
+//!
+//! With the `cycle-detection` feature, [`apply_ops`] replays sequences of
+//! [`Op`]s which create, clone, adopt, unadopt, and drop `Rc`s and checks the
+//! object graph after each step. It is intended to be driven by a fuzzer.
+//!
+//! `CountingAllocator` must be installed as the [global allocator] of the
+//! binary under test, for example an integration test or a doctest.
+//!
+//! [`Adopt::adopt_unchecked`]: crate::Adopt::adopt_unchecked
+//! [`Adopt::unadopt`]: crate::Adopt::unadopt
+//! [global allocator]: std::alloc#the-global_allocator-attribute
+//!
+//! # Examples
+//!
+//! Assert that a self-referential structure is fully deallocated:
+//!
+//! ```
+//! # #[cfg(feature = "cycle-detection")]
+//! # fn main() {
+//! use std::cell::RefCell;
+//!
+//! use cactusref::testing::{CountingAllocator, LeakDetector};
+//! use cactusref::{Adopt, Rc};
+//!
+//! #[global_allocator]
+//! static ALLOCATOR: CountingAllocator = CountingAllocator;
+//!
+//! struct Node {
+//!     next: RefCell<Option<Rc<Node>>>,
+//! }
+//!
+//! LeakDetector::new("ring").check(|| {
+//!     let first = Rc::new(Node { next: RefCell::new(None) });
+//!     let second = Rc::new(Node { next: RefCell::new(Some(Rc::clone(&first))) });
+//!     *first.next.borrow_mut() = Some(Rc::clone(&second));
+//!     unsafe {
+//!         Rc::adopt_unchecked(&first, &second);
+//!         Rc::adopt_unchecked(&second, &first);
+//!     }
+//! });
+//! # }
+//! # #[cfg(not(feature = "cycle-detection"))]
+//! # fn main() {}
+//! ```
+//!
+//! Without adoptions, the cycle leaks:
+//!
+//! ```should_panic
+//! use std::cell::RefCell;
+//!
+//! use cactusref::testing::{CountingAllocator, LeakDetector};
+//! use cactusref::Rc;
+//!
+//! #[global_allocator]
+//! static ALLOCATOR: CountingAllocator = CountingAllocator;
+//!
+//! struct Node {
+//!     next: RefCell<Option<Rc<Node>>>,
+//! }
+//!
+//! LeakDetector::new("ring without adoptions").check(|| {
+//!     let first = Rc::new(Node { next: RefCell::new(None) });
+//!     let second = Rc::new(Node { next: RefCell::new(Some(Rc::clone(&first))) });
+//!     *first.next.borrow_mut() = Some(Rc::clone(&second));
+//! });
+//! ```
+
+use core::cell::Cell;
+#[cfg(feature = "cycle-detection")]
+use core::cell::RefCell;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::boxed::Box;
+use std::hint;
+#[cfg(feature = "cycle-detection")]
+use std::rc::Rc as StdRc;
+use std::thread_local;
+#[cfg(feature = "cycle-detection")]
+use std::vec::Vec;
+
+#[cfg(feature = "cycle-detection")]
+use crate::{Adopt, Rc};
+
+thread_local! {
+    static ALLOCATED: Cell<isize> = const { Cell::new(0) };
+}
+
+fn record(delta: isize) {
+    // The thread-local may already be destroyed while the thread exits.
+    let _ = ALLOCATED.try_with(|allocated| allocated.set(allocated.get().wrapping_add(delta)));
+}
+
+/// A [`GlobalAlloc`] which delegates to [`System`] and counts the bytes
+/// allocated on each thread.
+///
+/// Memory is counted against the thread which allocates or frees it, so
+/// memory which is freed on a different thread than it was allocated on is
+/// miscounted. [`LeakDetector`] is intended for single-threaded code, like
+/// graphs of [`Rc`]s.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    #[inline]
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record(layout.size() as isize);
+        }
+        ptr
+    }
+
+    #[inline]
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record(layout.size() as isize);
+        }
+        ptr
+    }
+
+    #[inline]
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record(-(layout.size() as isize));
+    }
+
+    #[inline]
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record(new_size as isize - layout.size() as isize);
+        }
+        new_ptr
+    }
+}
+
+/// Returns the number of bytes allocated and not yet freed by the current
+/// thread since it started.
+///
+/// This is always 0 if [`CountingAllocator`] is not the global allocator.
+#[must_use]
+pub fn allocated_bytes() -> isize {
+    ALLOCATED.with(Cell::get)
+}
+
+/// Asserts that a closure frees all memory it allocates.
+///
+/// `LeakDetector` requires [`CountingAllocator`] to be installed as the
+/// global allocator. See the [module-level documentation](self) for examples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LeakDetector {
+    name: &'static str,
+    tolerance: usize,
+}
+
+impl LeakDetector {
+    /// Create a new leak detector with the given name, which is included in
+    /// the panic message if a leak is detected.
+    ///
+    /// The detector tolerates no leaked bytes by default.
+    #[must_use]
+    pub const fn new(name: &'static str) -> Self {
+        Self { name, tolerance: 0 }
+    }
+
+    /// Set the number of bytes the closure may leave allocated without being
+    /// reported as a leak.
+    ///
+    /// This can be used to permit lazily initialized thread-local or global
+    /// state in the code under test.
+    #[must_use]
+    pub const fn with_tolerance(mut self, bytes: usize) -> Self {
+        self.tolerance = bytes;
+        self
+    }
+
+    /// Run `f` and assert that the memory it leaves allocated on the current
+    /// thread is within the tolerance of this detector.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` leaks more bytes than the tolerance of this detector, or
+    /// if [`CountingAllocator`] is not the global allocator.
+    pub fn check<F>(&self, f: F)
+    where
+        F: FnOnce(),
+    {
+        assert!(
+            is_installed(),
+            "CountingAllocator must be the global allocator to detect leaks"
+        );
+        let before = allocated_bytes();
+        f();
+        let leaked = allocated_bytes().wrapping_sub(before);
+        assert!(
+            leaked <= 0 || leaked.unsigned_abs() <= self.tolerance,
+            "{}: leaked {leaked} bytes, which exceeds the tolerance of {} bytes",
+            self.name,
+            self.tolerance,
+        );
+    }
+}
+
+fn is_installed() -> bool {
+    let before = allocated_bytes();
+    let probe = hint::black_box(Box::new(0_u64));
+    let installed = allocated_bytes() != before;
+    drop(probe);
+    installed
+}
+
+/// An operation on the node table maintained by [`apply_ops`].
+///
+/// Operands are indexes into the table of `Rc` handles. Each [`Op::NewNode`]
+/// and [`Op::Clone`] appends a handle to the table. Operations which refer to
+/// an index that is out of bounds or whose handle has been dropped are
+/// ignored, so every sequence of `Op`s is valid input.
+#[cfg(feature = "cycle-detection")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Op {
+    /// Allocate a new node and append a handle to it.
+    NewNode,
+    /// Clone the handle at the given index and append the clone.
+    Clone(usize),
+    /// Store a strong reference to the node of the second handle in the node
+    /// of the first handle and record the adoption.
+    Adopt(usize, usize),
+    /// Remove a strong reference to the node of the second handle from the
+    /// node of the first handle, if it has one, and record the unadoption.
+    Unadopt(usize, usize),
+    /// Drop the handle at the given index.
+    Drop(usize),
+}
+
+#[cfg(feature = "cycle-detection")]
+struct Node {
+    id: usize,
+    children: RefCell<Vec<Rc<Node>>>,
+    dropped: StdRc<RefCell<Vec<bool>>>,
+}
+
+#[cfg(feature = "cycle-detection")]
+impl Drop for Node {
+    fn drop(&mut self) {
+        let mut dropped = self.dropped.borrow_mut();
+        assert!(!dropped[self.id], "node {} dropped twice", self.id);
+        dropped[self.id] = true;
+    }
+}
+
+/// Replay `ops` against a table of `Rc` handles and check the object graph
+/// after each operation.
+///
+/// Every adoption made by the interpreter is backed by a strong reference
+/// stored in the adopting node, so the object graph is always well formed and
+/// every node must be deallocated once all handles are dropped.
+///
+/// After each operation, `apply_ops` asserts that:
+///
+/// - No node has been dropped more than once.
+/// - No node reachable from a live handle has been dropped.
+/// - [`Rc::validate_graph`] succeeds for every live handle, in builds with
+///   debug assertions enabled.
+///
+/// Once all operations are applied, the remaining handles are dropped and
+/// `apply_ops` asserts that every node has been dropped. If
+/// [`CountingAllocator`] is the global allocator, `apply_ops` also asserts
+/// that it freed exactly the bytes it allocated.
+///
+/// # Panics
+///
+/// Panics if any of the above checks fail.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "cycle-detection")]
+/// # fn main() {
+/// use cactusref::testing::{apply_ops, Op};
+///
+/// // A ring of two nodes.
+/// apply_ops(&[
+///     Op::NewNode,
+///     Op::NewNode,
+///     Op::Adopt(0, 1),
+///     Op::Adopt(1, 0),
+///     Op::Drop(0),
+///     Op::Drop(1),
+/// ]);
+/// # }
+/// # #[cfg(not(feature = "cycle-detection"))]
+/// # fn main() {}
+/// ```
+#[cfg(feature = "cycle-detection")]
+pub fn apply_ops(ops: &[Op]) {
+    let before = allocated_bytes();
+    let dropped = StdRc::new(RefCell::new(Vec::new()));
+    let mut handles: Vec<Option<Rc<Node>>> = Vec::new();
+
+    for &op in ops {
+        match op {
+            Op::NewNode => {
+                let id = dropped.borrow().len();
+                dropped.borrow_mut().push(false);
+                handles.push(Some(Rc::new(Node {
+                    id,
+                    children: RefCell::new(Vec::new()),
+                    dropped: StdRc::clone(&dropped),
+                })));
+            }
+            Op::Clone(idx) => {
+                if let Some(Some(handle)) = handles.get(idx) {
+                    let clone = Rc::clone(handle);
+                    handles.push(Some(clone));
+                }
+            }
+            Op::Adopt(parent, child) => {
+                if let (Some(Some(parent)), Some(Some(child))) =
+                    (handles.get(parent), handles.get(child))
+                {
+                    // SAFETY: The adopted clone is stored in `parent` and is
+                    // unadopted before it is removed.
+                    let child = unsafe { Rc::adopt_and_keep(parent, child) };
+                    parent.children.borrow_mut().push(child);
+                }
+            }
+            Op::Unadopt(parent, child) => {
+                if let (Some(Some(parent)), Some(Some(child))) =
+                    (handles.get(parent), handles.get(child))
+                {
+                    let mut children = parent.children.borrow_mut();
+                    let pos = children.iter().position(|item| Rc::ptr_eq(item, child));
+                    let removed = pos.map(|pos| children.remove(pos));
+                    drop(children);
+                    if let Some(removed) = removed {
+                        Rc::unadopt(parent, &removed);
+                    }
+                }
+            }
+            Op::Drop(idx) => {
+                if let Some(handle) = handles.get_mut(idx) {
+                    *handle = None;
+                }
+            }
+        }
+        check_live(&handles, &dropped.borrow());
+    }
+
+    drop(handles);
+    let leaked = dropped.borrow().iter().filter(|&&dropped| !dropped).count();
+    assert_eq!(leaked, 0, "{leaked} nodes were not dropped");
+    drop(dropped);
+    if is_installed() {
+        assert_eq!(
+            allocated_bytes(),
+            before,
+            "apply_ops did not free exactly the bytes it allocated"
+        );
+    }
+}
+
+#[cfg(feature = "cycle-detection")]
+fn check_live(handles: &[Option<Rc<Node>>], dropped: &[bool]) {
+    for handle in handles.iter().flatten() {
+        assert!(
+            !dropped[handle.id],
+            "node {} dropped while a handle is live",
+            handle.id
+        );
+        for child in handle.children.borrow().iter() {
+            assert!(
+                !dropped[child.id],
+                "node {} dropped while it is adopted",
+                child.id
+            );
+        }
+        #[cfg(debug_assertions)]
+        if let Err(err) = Rc::validate_graph(handle) {
+            panic!("node {}: {err}", handle.id);
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! Helpers for collections of [`Weak`] pointers.
+
+use alloc::vec::Vec;
+
+use crate::Weak;
+
+/// Removes every [`Weak`] from `weaks` which can no longer be upgraded.
+///
+/// Collections of `Weak`s, like back references to parent nodes or
+/// self-referential collections, accumulate dangling `Weak`s as the values
+/// they point to are dropped. Each dangling `Weak` keeps its allocation alive
+/// until it is dropped, so pruning them releases the allocations once no
+/// other `Weak`s point to them.
+///
+/// A `Weak` is removed if [`Weak::upgrade`] would return [`None`]. This
+/// includes `Weak`s created with [`Weak::new`] and `Weak`s to members of
+/// orphaned cycles that have been collected. Removed `Weak`s are dropped,
+/// which decrements the weak count of their allocations. The order of the
+/// remaining `Weak`s is preserved.
+///
+/// # Examples
+///
+/// ```
+/// use cactusref::{retain_live, Rc, Weak};
+///
+/// let live = Rc::new("live");
+/// let dropped = Rc::new("dropped");
+/// let mut weaks = vec![Rc::downgrade(&live), Rc::downgrade(&dropped), Weak::new()];
+/// drop(dropped);
+///
+/// retain_live(&mut weaks);
+/// assert_eq!(weaks.len(), 1);
+/// assert!(weaks[0].points_to(&live));
+/// assert_eq!(Rc::weak_count(&live), 1);
+/// ```
+pub fn retain_live<T>(weaks: &mut Vec<Weak<T>>) {
+    // A `Weak` can be upgraded if and only if its strong count is non-zero.
+    // Checking the count avoids creating and dropping an `Rc` for every live
+    // `Weak`.
+    weaks.retain(|weak| weak.strong_count() > 0);
+}
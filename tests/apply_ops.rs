@@ -0,0 +1,82 @@
+#![warn(clippy::all)]
+#![warn(clippy::pedantic)]
+#![cfg(all(feature = "testing", feature = "cycle-detection"))]
+
+use cactusref::testing::{apply_ops, CountingAllocator, Op};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+// The two node ring from the README.
+fn readme_ring() -> Vec<Op> {
+    vec![
+        Op::NewNode,
+        Op::NewNode,
+        Op::Adopt(1, 0),
+        Op::Adopt(0, 1),
+        Op::Clone(0),
+        Op::Drop(2),
+        Op::Drop(0),
+        Op::Drop(1),
+    ]
+}
+
+// Two rings of ten nodes joined into one graph, from
+// `leak_adopt_with_members_in_multiple_cycles`.
+fn multiple_cycles() -> Vec<Op> {
+    let mut ops = vec![];
+    for first in [0, 10] {
+        ops.push(Op::NewNode);
+        for idx in first + 1..first + 10 {
+            ops.push(Op::NewNode);
+            ops.push(Op::Adopt(idx, idx - 1));
+        }
+        ops.push(Op::Adopt(first, first + 9));
+    }
+    ops.push(Op::Adopt(10, 0));
+    ops.push(Op::Adopt(0, 10));
+    ops.extend((0..20).map(Op::Drop));
+    ops
+}
+
+#[test]
+fn apply_ops_readme_ring() {
+    env_logger::Builder::from_env("CACTUS_LOG").init();
+
+    apply_ops(&readme_ring());
+}
+
+#[test]
+fn apply_ops_multiple_cycles() {
+    apply_ops(&multiple_cycles());
+}
+
+#[test]
+fn apply_ops_ignores_invalid_handles() {
+    apply_ops(&[
+        Op::Clone(0),
+        Op::NewNode,
+        Op::Adopt(0, 7),
+        Op::Unadopt(0, 0),
+        Op::Drop(0),
+        Op::Drop(0),
+        Op::Clone(0),
+        Op::Adopt(0, 0),
+    ]);
+}
+
+#[test]
+fn apply_ops_unadopt() {
+    apply_ops(&[
+        Op::NewNode,
+        Op::NewNode,
+        Op::Adopt(0, 1),
+        Op::Adopt(1, 0),
+        Op::Adopt(0, 0),
+        Op::Unadopt(1, 0),
+        Op::Drop(0),
+        Op::Unadopt(1, 1),
+        Op::Adopt(1, 1),
+        Op::Drop(1),
+    ]);
+}
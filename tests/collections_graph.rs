@@ -0,0 +1,130 @@
+#![warn(clippy::all)]
+#![warn(clippy::pedantic)]
+#![cfg(feature = "cycle-detection")]
+
+use std::cell::Cell;
+use std::rc::Rc as StdRc;
+
+use cactusref::collections::{Graph, NodeId};
+
+#[cfg(feature = "testing")]
+#[global_allocator]
+static ALLOCATOR: cactusref::testing::CountingAllocator = cactusref::testing::CountingAllocator;
+
+struct DropCounter {
+    drops: StdRc<Cell<usize>>,
+}
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        self.drops.set(self.drops.get() + 1);
+    }
+}
+
+fn strongly_connected(graph: &mut Graph<DropCounter>, drops: &StdRc<Cell<usize>>) -> Vec<NodeId> {
+    let ids = (0..5)
+        .map(|_| {
+            graph.add_node(DropCounter {
+                drops: StdRc::clone(drops),
+            })
+        })
+        .collect::<Vec<_>>();
+    for &from in &ids {
+        for &to in &ids {
+            assert!(graph.add_edge(from, to));
+        }
+    }
+    ids
+}
+
+#[test]
+fn graph_add_remove_edges() {
+    env_logger::Builder::from_env("CACTUS_LOG").init();
+
+    let mut graph = Graph::new();
+    assert!(graph.is_empty());
+    let a = graph.add_node('a');
+    let b = graph.add_node('b');
+    let c = graph.add_node('c');
+    assert_eq!(graph.node_count(), 3);
+    assert_eq!(graph.get(b), Some(&'b'));
+
+    assert!(graph.add_edge(a, b));
+    assert!(graph.add_edge(a, c));
+    assert!(graph.add_edge(c, c));
+    assert!(!graph.add_edge(a, b));
+    assert_eq!(graph.edge_count(), 3);
+    assert_eq!(graph.neighbors(a).collect::<Vec<_>>(), [b, c]);
+    assert!(graph.contains_edge(c, c));
+    assert!(!graph.contains_edge(b, a));
+
+    assert!(graph.remove_edge(a, b));
+    assert!(!graph.remove_edge(a, b));
+    assert_eq!(graph.neighbors(a).collect::<Vec<_>>(), [c]);
+
+    assert_eq!(graph.remove_node(c), Some('c'));
+    assert_eq!(graph.remove_node(c), None);
+    assert_eq!(graph.get(c), None);
+    assert!(!graph.contains_edge(a, c));
+    assert_eq!(graph.edge_count(), 0);
+    assert_eq!(graph.node_ids().collect::<Vec<_>>(), [a, b]);
+}
+
+#[test]
+fn graph_drop_deallocates_strongly_connected_component() {
+    let drops = StdRc::new(Cell::new(0));
+    let mut graph = Graph::new();
+    let component = strongly_connected(&mut graph, &drops);
+    let tail = graph.add_node(DropCounter {
+        drops: StdRc::clone(&drops),
+    });
+    assert!(graph.add_edge(tail, component[0]));
+    assert_eq!(drops.get(), 0);
+    drop(graph);
+    assert_eq!(drops.get(), 6);
+}
+
+#[test]
+fn graph_detach_strongly_connected_component() {
+    let drops = StdRc::new(Cell::new(0));
+    let mut graph = Graph::new();
+    let root = graph.add_node(DropCounter {
+        drops: StdRc::clone(&drops),
+    });
+    let component = strongly_connected(&mut graph, &drops);
+    assert!(graph.add_edge(root, component[0]));
+    assert!(graph.add_edge(component[0], root));
+
+    // Detach the component from the root.
+    assert!(graph.remove_edge(root, component[0]));
+    assert!(graph.remove_edge(component[0], root));
+    assert_eq!(drops.get(), 0);
+
+    for (removed, id) in component.into_iter().enumerate() {
+        let value = graph.remove_node(id).unwrap();
+        assert_eq!(drops.get(), removed);
+        drop(value);
+    }
+    assert_eq!(drops.get(), 5);
+    assert_eq!(graph.node_ids().collect::<Vec<_>>(), [root]);
+    assert_eq!(graph.edge_count(), 0);
+    drop(graph);
+    assert_eq!(drops.get(), 6);
+}
+
+#[test]
+#[cfg(feature = "testing")]
+fn graph_leak_check() {
+    use cactusref::testing::LeakDetector;
+
+    LeakDetector::new("graph").check(|| {
+        let drops = StdRc::new(Cell::new(0));
+        let mut graph = Graph::new();
+        let first = strongly_connected(&mut graph, &drops);
+        let second = strongly_connected(&mut graph, &drops);
+        assert!(graph.add_edge(first[0], second[0]));
+        assert!(graph.add_edge(second[0], first[0]));
+        assert!(graph.remove_edge(first[0], second[0]));
+        graph.remove_node(first[1]);
+    });
+}
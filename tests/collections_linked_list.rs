@@ -0,0 +1,102 @@
+#![warn(clippy::all)]
+#![warn(clippy::pedantic)]
+#![cfg(feature = "cycle-detection")]
+
+use std::cell::Cell;
+use std::rc::Rc as StdRc;
+
+use cactusref::collections::LinkedList;
+
+#[cfg(feature = "testing")]
+#[global_allocator]
+static ALLOCATOR: cactusref::testing::CountingAllocator = cactusref::testing::CountingAllocator;
+
+struct DropCounter {
+    id: usize,
+    drops: StdRc<Cell<usize>>,
+}
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        self.drops.set(self.drops.get() + 1);
+    }
+}
+
+#[test]
+fn linked_list_push_pop() {
+    env_logger::Builder::from_env("CACTUS_LOG").init();
+
+    let mut list = LinkedList::new();
+    assert!(list.is_empty());
+    assert_eq!(list.pop_front(), None);
+
+    list.extend(0..5);
+    assert_eq!(list.len(), 5);
+    assert_eq!(list.front(), Some(&0));
+    assert_eq!(list.back(), Some(&4));
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), [0, 1, 2, 3, 4]);
+
+    assert_eq!(list.pop_front(), Some(0));
+    assert_eq!(list.pop_front(), Some(1));
+    list.push_back(5);
+    assert_eq!(list.pop_front(), Some(2));
+    list.push_back(6);
+    list.push_back(7);
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), [3, 4, 5, 6, 7]);
+    assert_eq!(list.iter().len(), 5);
+
+    while list.len() > 1 {
+        list.pop_front();
+    }
+    assert_eq!(list.front(), Some(&7));
+    assert_eq!(list.back(), Some(&7));
+    assert_eq!(list.pop_front(), Some(7));
+    assert!(list.is_empty());
+    assert_eq!(list.iter().next(), None);
+
+    list.push_back(8);
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), [8]);
+}
+
+#[test]
+fn linked_list_drop_deallocates_all_values() {
+    let drops = StdRc::new(Cell::new(0));
+    let mut list = (0..10)
+        .map(|id| DropCounter {
+            id,
+            drops: StdRc::clone(&drops),
+        })
+        .collect::<LinkedList<_>>();
+
+    let popped = list.pop_front().unwrap();
+    assert_eq!(popped.id, 0);
+    assert_eq!(drops.get(), 0);
+    drop(popped);
+    assert_eq!(drops.get(), 1);
+
+    assert_eq!(
+        list.iter().map(|counter| counter.id).collect::<Vec<_>>(),
+        (1..10).collect::<Vec<_>>()
+    );
+    drop(list);
+    assert_eq!(drops.get(), 10);
+}
+
+#[test]
+#[cfg(feature = "testing")]
+fn linked_list_leak_check() {
+    use cactusref::testing::LeakDetector;
+
+    LeakDetector::new("linked list").check(|| {
+        let mut list = (0..100).collect::<LinkedList<_>>();
+        for _ in 0..50 {
+            list.pop_front();
+        }
+        list.extend(100..150);
+        assert_eq!(list.len(), 100);
+    });
+    LeakDetector::new("drained linked list").check(|| {
+        let mut list = (0..100).collect::<LinkedList<_>>();
+        while list.pop_front().is_some() {}
+    });
+}
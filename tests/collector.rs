@@ -0,0 +1,120 @@
+#![warn(clippy::all)]
+#![warn(clippy::pedantic)]
+#![cfg(all(feature = "cycle-detection", feature = "std"))]
+
+use cactusref::{collector, set_trace_budget, Rc, Weak};
+
+mod common;
+
+use common::{dropped, node_ring, Node};
+
+#[test]
+fn deferred_cycle_is_freed_on_collect() {
+    env_logger::Builder::from_env("CACTUS_LOG").init();
+
+    log::info!("deferred collection of an orphaned ring");
+
+    let nodes = node_ring(10);
+    let weaks = nodes.iter().map(Rc::downgrade).collect::<Vec<_>>();
+
+    unsafe {
+        collector::defer();
+    }
+    assert!(collector::is_deferred());
+    drop(nodes);
+
+    assert_eq!(dropped().len(), 0);
+    // Every dropped member of the ring is a candidate.
+    assert_eq!(collector::pending(), 10);
+    assert!(weaks.iter().all(|weak| weak.strong_count() > 0));
+
+    assert_eq!(collector::collect(), 10);
+    assert_eq!(dropped().len(), 10);
+    assert_eq!(collector::pending(), 0);
+    assert!(weaks.iter().all(|weak| weak.upgrade().is_none()));
+
+    collector::immediate();
+}
+
+#[test]
+fn deferred_cycle_weak_upgrade() {
+    let nodes = node_ring(3);
+    let weak = Rc::downgrade(&nodes[0]);
+
+    unsafe {
+        collector::defer();
+    }
+    drop(nodes);
+
+    // Members of a pending cycle are kept alive until they are collected.
+    let upgraded = weak.upgrade();
+    assert!(upgraded.is_some());
+    drop(upgraded);
+    assert!(weak.upgrade().is_some());
+    assert_eq!(dropped().len(), 0);
+
+    collector::collect();
+    assert!(weak.upgrade().is_none());
+    assert_eq!(dropped().len(), 3);
+
+    collector::immediate();
+}
+
+#[test]
+fn deferred_cycle_resurrected_before_collect() {
+    let nodes = node_ring(3);
+    let weak = Rc::downgrade(&nodes[0]);
+
+    unsafe {
+        collector::defer();
+    }
+    drop(nodes);
+    assert_eq!(collector::pending(), 3);
+
+    // Upgrading a member of a pending cycle makes it reachable again.
+    let resurrected = weak.upgrade().unwrap();
+    collector::collect();
+    assert_eq!(dropped().len(), 0);
+    assert!(weak.upgrade().is_some());
+
+    collector::immediate();
+    drop(resurrected);
+    assert_eq!(dropped().len(), 3);
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn immediate_mode_frees_cycles_on_drop() {
+    let nodes = node_ring(3);
+    let weak: Weak<Node> = Rc::downgrade(&nodes[0]);
+
+    assert!(!collector::is_deferred());
+    drop(nodes);
+
+    assert_eq!(collector::pending(), 0);
+    assert_eq!(dropped().len(), 3);
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn deferred_drop_does_not_trace() {
+    let nodes = node_ring(3);
+    let weak = Rc::downgrade(&nodes[0]);
+
+    // A trace of the ring exceeds this budget, so a trace during `drop` would
+    // leak the ring instead of collecting it.
+    set_trace_budget(1);
+    unsafe {
+        collector::defer();
+    }
+    drop(nodes);
+    assert_eq!(collector::pending(), 3);
+    assert!(weak.upgrade().is_some());
+
+    set_trace_budget(usize::MAX);
+    collector::collect();
+    assert_eq!(dropped().len(), 3);
+    assert!(weak.upgrade().is_none());
+
+    collector::immediate();
+}
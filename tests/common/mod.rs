@@ -0,0 +1,92 @@
+//! Fixtures shared by the integration tests.
+
+// Each integration test only uses some of these fixtures.
+#![allow(dead_code)]
+
+use std::cell::RefCell;
+
+use cactusref::{Adopt, Rc};
+
+thread_local! {
+    static DROPPED: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A labeled node which owns the next node in a ring.
+///
+/// Dropping a `Node` records its label, see [`dropped`].
+pub struct Node {
+    pub label: usize,
+    pub next: RefCell<Option<Rc<Node>>>,
+}
+
+impl Node {
+    pub fn new(label: usize) -> Self {
+        Self {
+            label,
+            next: RefCell::new(None),
+        }
+    }
+}
+
+impl Drop for Node {
+    fn drop(&mut self) {
+        // Nodes in cycles pending deferred collection may be dropped after
+        // the drop log is destroyed when the thread exits.
+        let _ = DROPPED.try_with(|dropped| dropped.borrow_mut().push(self.label));
+    }
+}
+
+/// Returns the labels of the `Node`s dropped on this thread, in drop order.
+pub fn dropped() -> Vec<usize> {
+    DROPPED.with(|dropped| dropped.borrow().clone())
+}
+
+/// Forgets the `Node`s dropped on this thread so far.
+pub fn clear_dropped() {
+    DROPPED.with(|dropped| dropped.borrow_mut().clear());
+}
+
+/// Builds a ring of `len` `Node`s labeled `0..len`.
+pub fn node_ring(len: usize) -> Vec<Rc<Node>> {
+    ring((0..len).map(Node::new), |node, next| {
+        *node.next.borrow_mut() = Some(next);
+    })
+}
+
+/// Builds a ring of `Rc`s from `values` in which every node owns and adopts
+/// the next node. The last node owns the first node.
+///
+/// `set_next` stores the strong reference to the next node in a node.
+pub fn ring<T>(
+    values: impl IntoIterator<Item = T>,
+    set_next: impl Fn(&Rc<T>, Rc<T>),
+) -> Vec<Rc<T>> {
+    build_ring(values, set_next, true)
+}
+
+/// Builds a ring like [`ring`] without adopting the next nodes, which leaks
+/// the ring once it is dropped.
+pub fn unadopted_ring<T>(
+    values: impl IntoIterator<Item = T>,
+    set_next: impl Fn(&Rc<T>, Rc<T>),
+) -> Vec<Rc<T>> {
+    build_ring(values, set_next, false)
+}
+
+fn build_ring<T>(
+    values: impl IntoIterator<Item = T>,
+    set_next: impl Fn(&Rc<T>, Rc<T>),
+    adopt: bool,
+) -> Vec<Rc<T>> {
+    let nodes = values.into_iter().map(Rc::new).collect::<Vec<_>>();
+    for (idx, node) in nodes.iter().enumerate() {
+        let next = Rc::clone(&nodes[(idx + 1) % nodes.len()]);
+        if adopt {
+            unsafe {
+                Rc::adopt_unchecked(node, &next);
+            }
+        }
+        set_next(node, next);
+    }
+    nodes
+}
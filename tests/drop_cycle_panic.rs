@@ -0,0 +1,49 @@
+#![warn(clippy::all)]
+#![warn(clippy::pedantic)]
+#![cfg(feature = "cycle-detection")]
+
+use std::cell::{Cell, RefCell};
+use std::panic::{self, AssertUnwindSafe};
+
+use cactusref::Rc;
+
+mod common;
+
+thread_local! {
+    static DROPS: Cell<usize> = const { Cell::new(0) };
+}
+
+struct Node {
+    panic_on_drop: bool,
+    next: RefCell<Option<Rc<Node>>>,
+}
+
+impl Drop for Node {
+    fn drop(&mut self) {
+        DROPS.with(|drops| drops.set(drops.get() + 1));
+        assert!(!self.panic_on_drop, "Node::drop panicked");
+    }
+}
+
+#[test]
+fn drop_cycle_panic() {
+    env_logger::Builder::from_env("CACTUS_LOG").init();
+
+    log::info!("panic in T::drop during cycle collection");
+
+    let nodes = (0..5).map(|idx| Node {
+        panic_on_drop: idx == 2,
+        next: RefCell::new(None),
+    });
+    let nodes = common::ring(nodes, |node, next| *node.next.borrow_mut() = Some(next));
+    let weaks = nodes.iter().map(Rc::downgrade).collect::<Vec<_>>();
+
+    let result = panic::catch_unwind(AssertUnwindSafe(move || drop(nodes)));
+    assert!(result.is_err());
+
+    assert_eq!(DROPS.with(Cell::get), 5);
+    for weak in &weaks {
+        assert!(weak.upgrade().is_none());
+        assert_eq!(weak.strong_count(), 0);
+    }
+}
@@ -0,0 +1,76 @@
+#![warn(clippy::all)]
+#![warn(clippy::pedantic)]
+#![cfg(all(feature = "std", feature = "cycle-detection"))]
+
+use cactusref::{drop_order, set_drop_order, Adopt, DropOrder, Rc};
+
+mod common;
+
+use common::{clear_dropped, dropped, node_ring, Node};
+
+// Build a ring of nodes and return the node labels sorted by the address of
+// their allocation.
+fn ring(count: usize) -> (Vec<Rc<Node>>, Vec<usize>) {
+    let nodes = node_ring(count);
+    let mut by_address = nodes.iter().collect::<Vec<_>>();
+    by_address.sort_by(|left, right| Rc::ptr_cmp(left, right));
+    let labels = by_address.iter().map(|node| node.label).collect();
+    (nodes, labels)
+}
+
+#[test]
+fn drop_order_address() {
+    env_logger::Builder::from_env("CACTUS_LOG").init();
+
+    log::info!("drop orphaned ring in address order");
+    assert_eq!(drop_order(), DropOrder::Unspecified);
+    set_drop_order(DropOrder::Address);
+
+    for _ in 0..10 {
+        clear_dropped();
+        let (nodes, expected) = ring(32);
+        drop(nodes);
+        let dropped = dropped();
+        assert_eq!(dropped, expected);
+    }
+}
+
+#[test]
+fn drop_order_unspecified_drops_all() {
+    let (nodes, mut expected) = ring(32);
+    drop(nodes);
+    let mut dropped = dropped();
+    dropped.sort_unstable();
+    expected.sort_unstable();
+    assert_eq!(dropped, expected);
+}
+
+#[test]
+fn drop_order_reverse_adoption() {
+    set_drop_order(DropOrder::ReverseAdoption);
+
+    for _ in 0..10 {
+        clear_dropped();
+        // Each node adopts the next, so the ring is a chain from head to tail
+        // closed by the tail adopting the head.
+        let (nodes, _) = ring(32);
+        drop(nodes);
+        let dropped = dropped();
+        assert_eq!(dropped, (0..32).rev().collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn drop_order_reverse_adoption_forgets_removed_links() {
+    set_drop_order(DropOrder::ReverseAdoption);
+    clear_dropped();
+
+    let (nodes, _) = ring(3);
+    // Replace the oldest adoption, which makes the head the newest adopter.
+    unsafe {
+        Rc::unadopt(&nodes[0], &nodes[1]);
+        Rc::adopt_unchecked(&nodes[0], &nodes[1]);
+    }
+    drop(nodes);
+    assert_eq!(dropped(), [0, 2, 1]);
+}
@@ -0,0 +1,69 @@
+#![warn(clippy::all)]
+#![warn(clippy::pedantic)]
+#![cfg(feature = "cycle-detection")]
+
+use std::cell::{Cell, RefCell};
+
+use cactusref::{Adopt, Rc};
+
+thread_local! {
+    static DROPS: Cell<usize> = const { Cell::new(0) };
+}
+
+struct Node {
+    links: RefCell<Vec<Rc<Node>>>,
+}
+
+impl Drop for Node {
+    fn drop(&mut self) {
+        DROPS.with(|drops| drops.set(drops.get() + 1));
+
+        // Mutate the bookkeeping of the cycle being collected.
+        let links = self.links.get_mut();
+        if let [first, rest @ ..] = links.as_slice() {
+            for other in rest {
+                Rc::unadopt(first, other);
+                unsafe {
+                    Rc::adopt_unchecked(other, first);
+                }
+                let clone = Rc::downgrade(other);
+                drop(clone);
+            }
+        }
+    }
+}
+
+fn fully_connected_graph(count: usize) -> Vec<Rc<Node>> {
+    let nodes = (0..count)
+        .map(|_| {
+            Rc::new(Node {
+                links: RefCell::new(vec![]),
+            })
+        })
+        .collect::<Vec<_>>();
+    for left in &nodes {
+        for right in &nodes {
+            let link = Rc::clone(right);
+            unsafe {
+                Rc::adopt_unchecked(left, &link);
+            }
+            left.links.borrow_mut().push(link);
+        }
+    }
+    nodes
+}
+
+#[test]
+fn drop_reentrant_adoption() {
+    env_logger::Builder::from_env("CACTUS_LOG").init();
+
+    log::info!("drop with reentrant adoption bookkeeping");
+
+    let nodes = fully_connected_graph(5);
+    let weaks = nodes.iter().map(Rc::downgrade).collect::<Vec<_>>();
+    drop(nodes);
+
+    assert_eq!(DROPS.with(Cell::get), 5);
+    assert!(weaks.iter().all(|weak| weak.upgrade().is_none()));
+    assert!(weaks.iter().all(|weak| weak.strong_count() == 0));
+}
@@ -0,0 +1,128 @@
+#![warn(clippy::all)]
+#![warn(clippy::pedantic)]
+#![cfg(feature = "cycle-detection")]
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc as StdRc;
+
+use cactusref::{Adopt, Rc};
+
+mod common;
+
+use common::{node_ring, Node};
+
+fn finalize_into(finalized: &StdRc<RefCell<Vec<usize>>>) -> Box<dyn FnOnce(&Node)> {
+    let finalized = StdRc::clone(finalized);
+    Box::new(move |node: &Node| finalized.borrow_mut().push(node.label))
+}
+
+#[test]
+fn finalizer_runs_once_for_unreachable_rc() {
+    env_logger::Builder::from_env("CACTUS_LOG").init();
+
+    log::info!("finalizer of an ungraphed Rc");
+
+    let finalized = StdRc::new(RefCell::new(vec![]));
+    let node = Rc::new(Node::new(7));
+    let clone = Rc::clone(&node);
+    Rc::set_finalizer(&node, finalize_into(&finalized));
+    assert!(!Rc::is_graphed(&node));
+
+    drop(node);
+    assert!(finalized.borrow().is_empty());
+    drop(clone);
+    assert_eq!(*finalized.borrow(), [7]);
+}
+
+#[test]
+fn replaced_finalizer_is_not_called() {
+    let calls = StdRc::new(Cell::new(0));
+    let node = Rc::new(());
+
+    let first = StdRc::clone(&calls);
+    Rc::set_finalizer(&node, Box::new(move |()| first.set(first.get() + 1)));
+    let second = StdRc::clone(&calls);
+    Rc::set_finalizer(&node, Box::new(move |()| second.set(second.get() + 10)));
+    // The replaced finalizer is dropped.
+    assert_eq!(StdRc::strong_count(&calls), 2);
+
+    drop(node);
+    assert_eq!(calls.get(), 10);
+    assert_eq!(StdRc::strong_count(&calls), 1);
+}
+
+#[test]
+fn finalizer_runs_once_for_each_cycle_member() {
+    let finalized = StdRc::new(RefCell::new(vec![]));
+    let nodes = node_ring(10);
+    for node in &nodes {
+        Rc::set_finalizer(node, finalize_into(&finalized));
+    }
+    let weaks = nodes.iter().map(Rc::downgrade).collect::<Vec<_>>();
+
+    drop(nodes);
+    assert!(weaks.iter().all(|weak| weak.upgrade().is_none()));
+
+    let mut finalized = finalized.borrow().clone();
+    finalized.sort_unstable();
+    assert_eq!(finalized, (0..10).collect::<Vec<_>>());
+}
+
+#[test]
+fn finalizer_runs_for_adopted_unreachable_rc() {
+    let finalized = StdRc::new(RefCell::new(vec![]));
+    let parent = Rc::new(Node::new(1));
+    let child = Rc::new(Node::new(2));
+    *parent.next.borrow_mut() = Some(Rc::clone(&child));
+    unsafe {
+        Rc::adopt_unchecked(&parent, &child);
+    }
+    Rc::set_finalizer(&parent, finalize_into(&finalized));
+    Rc::set_finalizer(&child, finalize_into(&finalized));
+
+    drop(child);
+    assert!(finalized.borrow().is_empty());
+    drop(parent);
+    assert_eq!(*finalized.borrow(), [1, 2]);
+}
+
+#[test]
+fn finalizer_is_dropped_on_unwrap() {
+    let calls = StdRc::new(Cell::new(0));
+    let node = Rc::new(5);
+    let counter = StdRc::clone(&calls);
+    Rc::set_finalizer(
+        &node,
+        Box::new(move |_: &i32| counter.set(counter.get() + 1)),
+    );
+
+    assert_eq!(Rc::try_unwrap(node), Ok(5));
+    assert_eq!(calls.get(), 0);
+    assert_eq!(StdRc::strong_count(&calls), 1);
+}
+
+#[test]
+fn finalizer_only_reads_through_value() {
+    let observed = StdRc::new(RefCell::new(vec![]));
+    let nodes = node_ring(3);
+    for node in &nodes {
+        let observed = StdRc::clone(&observed);
+        let me = Rc::downgrade(node);
+        Rc::set_finalizer(
+            node,
+            Box::new(move |node: &Node| {
+                // The allocation is dead, so it cannot be upgraded.
+                assert!(me.upgrade().is_none());
+                // Other members are still readable through `&T`.
+                let next = node.next.borrow();
+                let next = next.as_ref().map(|next| next.label);
+                observed.borrow_mut().push((node.label, next));
+            }),
+        );
+    }
+
+    drop(nodes);
+    let mut observed = observed.borrow().clone();
+    observed.sort_unstable();
+    assert_eq!(observed, [(0, Some(1)), (1, Some(2)), (2, Some(0))]);
+}
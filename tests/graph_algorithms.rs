@@ -0,0 +1,96 @@
+#![warn(clippy::all)]
+#![warn(clippy::pedantic)]
+#![cfg(feature = "graph-algorithms")]
+
+use std::cell::RefCell;
+
+use cactusref::{Adopt, Rc};
+
+const WHITE: u8 = 0;
+const GRAY: u8 = 1;
+const BLACK: u8 = 2;
+
+struct Node {
+    label: usize,
+    children: RefCell<Vec<Rc<Node>>>,
+}
+
+fn adopt(parent: &Rc<Node>, child: &Rc<Node>) {
+    let child = Rc::clone(child);
+    unsafe {
+        Rc::adopt_unchecked(parent, &child);
+    }
+    parent.children.borrow_mut().push(child);
+}
+
+// Tri-color mark of every node reachable from `roots`.
+fn mark(roots: &[&Rc<Node>]) {
+    let mut gray = Vec::new();
+    for &root in roots {
+        if Rc::mark(root) == WHITE {
+            Rc::set_mark(root, GRAY);
+            gray.push(Rc::clone(root));
+        }
+    }
+    while let Some(node) = gray.pop() {
+        for child in node.children.borrow().iter() {
+            if Rc::mark(child) == WHITE {
+                Rc::set_mark(child, GRAY);
+                gray.push(Rc::clone(child));
+            }
+        }
+        Rc::set_mark(&node, BLACK);
+    }
+}
+
+#[test]
+fn mark_sweep_with_mark_byte() {
+    env_logger::Builder::from_env("CACTUS_LOG").init();
+
+    let heap = (0..8)
+        .map(|label| {
+            Rc::new(Node {
+                label,
+                children: RefCell::new(Vec::new()),
+            })
+        })
+        .collect::<Vec<_>>();
+    // 0 -> 1 -> 2 -> 0 is reachable from the root, 3 -> 4 -> 3 and 5 -> 6
+    // are not, and 7 is isolated.
+    adopt(&heap[0], &heap[1]);
+    adopt(&heap[1], &heap[2]);
+    adopt(&heap[2], &heap[0]);
+    adopt(&heap[2], &heap[5]);
+    adopt(&heap[3], &heap[4]);
+    adopt(&heap[4], &heap[3]);
+    adopt(&heap[6], &heap[5]);
+
+    mark(&[&heap[0]]);
+    let live = heap
+        .iter()
+        .filter(|node| Rc::mark(node) == BLACK)
+        .map(|node| node.label)
+        .collect::<Vec<_>>();
+    let garbage = heap
+        .iter()
+        .filter(|node| Rc::mark(node) == WHITE)
+        .map(|node| node.label)
+        .collect::<Vec<_>>();
+    assert_eq!(live, [0, 1, 2, 5]);
+    assert_eq!(garbage, [3, 4, 6, 7]);
+
+    // Clearing marks in one graph leaves other graphs untouched.
+    Rc::set_mark(&heap[3], BLACK);
+    Rc::clear_marks_in_graph(&heap[6]);
+    assert!(heap
+        .iter()
+        .all(|node| node.label == 3 || Rc::mark(node) == WHITE));
+    Rc::clear_marks_in_graph(&heap[4]);
+    assert!(heap.iter().all(|node| Rc::mark(node) == WHITE));
+
+    // Marks do not affect cycle collection.
+    mark(&[&heap[0], &heap[3]]);
+    let weaks = heap.iter().map(Rc::downgrade).collect::<Vec<_>>();
+    drop(heap);
+    assert!(weaks.iter().all(|weak| weak.upgrade().is_none()));
+}
@@ -1,5 +1,6 @@
 #![warn(clippy::all)]
 #![warn(clippy::pedantic)]
+#![cfg(feature = "cycle-detection")]
 
 use cactusref::{Adopt, Rc};
 
@@ -1,7 +1,11 @@
 #![warn(clippy::all)]
 #![warn(clippy::pedantic)]
+#![cfg(feature = "cycle-detection")]
 
 use cactusref::{Adopt, Rc};
+use core::cell::RefCell;
+
+mod common;
 
 #[test]
 fn leak_adopt_with_members_in_multiple_cycles() {
@@ -46,3 +50,66 @@ fn leak_adopt_with_members_in_multiple_cycles() {
     drop(group2);
     drop(group1);
 }
+
+struct Node {
+    value: usize,
+    owned: RefCell<Vec<Rc<Node>>>,
+}
+
+impl Node {
+    fn ring(values: impl IntoIterator<Item = usize>) -> Vec<Rc<Self>> {
+        let nodes = values.into_iter().map(|value| Self {
+            value,
+            owned: RefCell::new(Vec::new()),
+        });
+        common::ring(nodes, |node, next| node.owned.borrow_mut().push(next))
+    }
+
+    fn adopt(this: &Rc<Self>, other: &Rc<Self>) {
+        let other = Rc::clone(other);
+        unsafe {
+            Rc::adopt_unchecked(this, &other);
+        }
+        this.owned.borrow_mut().push(other);
+    }
+}
+
+#[test]
+fn graph_nodes_with_members_in_multiple_cycles() {
+    let group1 = Node::ring(0..10);
+    let group2 = Node::ring(100..110);
+    // join the two cycles
+    Node::adopt(&group1[0], &group2[0]);
+    Node::adopt(&group2[0], &group1[0]);
+
+    let expected = (0..10).chain(100..110).collect::<Vec<_>>();
+    for member in [&group1[0], &group2[0], &group2[9]] {
+        let nodes = Rc::graph_nodes(member);
+        let mut values = nodes.iter().map(|node| node.value).collect::<Vec<_>>();
+        values.sort_unstable();
+        assert_eq!(values, expected);
+        assert!(nodes.iter().any(|node| Rc::ptr_eq(node, member)));
+    }
+    // The returned `Rc`s have been dropped.
+    assert_eq!(Rc::strong_count_excluding_cycle(&group1[0]), 1);
+
+    let weak = Rc::downgrade(&group1[0]);
+    drop(group1);
+    drop(group2);
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn graph_nodes_keeps_ring_alive() {
+    let ring = Node::ring(0..10);
+    let weaks = ring.iter().map(Rc::downgrade).collect::<Vec<_>>();
+
+    let snapshot = Rc::graph_nodes(&ring[0]);
+    assert_eq!(snapshot.len(), 10);
+    drop(ring);
+    // The snapshot owns the ring, so no member is collected.
+    assert!(weaks.iter().all(|weak| weak.upgrade().is_some()));
+
+    drop(snapshot);
+    assert!(weaks.iter().all(|weak| weak.upgrade().is_none()));
+}
@@ -0,0 +1,64 @@
+#![warn(clippy::all)]
+#![warn(clippy::pedantic)]
+#![cfg(feature = "cycle-detection")]
+
+use std::cell::RefCell;
+
+use cactusref::Rc;
+
+struct Node {
+    children: RefCell<Vec<Rc<Node>>>,
+}
+
+impl Node {
+    fn new() -> Rc<Self> {
+        Rc::new(Self {
+            children: RefCell::new(Vec::new()),
+        })
+    }
+
+    fn adopt(this: &Rc<Self>, child: &Rc<Self>) {
+        let child = unsafe { Rc::adopt_and_keep(this, child) };
+        this.children.borrow_mut().push(child);
+    }
+}
+
+#[test]
+fn leak_cycle_adopting_shared_child() {
+    env_logger::Builder::from_env("CACTUS_LOG").init();
+
+    log::info!("orphaned cycle which adopts a node with an external owner");
+
+    let first = Node::new();
+    let second = Node::new();
+    let shared = Node::new();
+    Node::adopt(&first, &second);
+    Node::adopt(&second, &first);
+    Node::adopt(&second, &shared);
+
+    let weak_first = Rc::downgrade(&first);
+    let weak_second = Rc::downgrade(&second);
+    drop(first);
+    drop(second);
+    // `shared` does not keep the cycle alive.
+    assert!(weak_first.upgrade().is_none());
+    assert!(weak_second.upgrade().is_none());
+    assert_eq!(Rc::strong_count(&shared), 1);
+    assert!(!Rc::is_graphed(&shared));
+    drop(shared);
+}
+
+#[test]
+fn leak_self_adoption_with_shared_child() {
+    let node = Node::new();
+    let shared = Node::new();
+    Node::adopt(&node, &node);
+    Node::adopt(&node, &shared);
+    assert_eq!(Rc::cycle_len(&node), Some(1));
+
+    let weak = Rc::downgrade(&node);
+    drop(node);
+    assert!(weak.upgrade().is_none());
+    assert_eq!(Rc::strong_count(&shared), 1);
+    drop(shared);
+}
@@ -0,0 +1,56 @@
+#![warn(clippy::all)]
+#![warn(clippy::pedantic)]
+#![cfg(all(feature = "testing", feature = "cycle-detection"))]
+
+use std::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
+
+use cactusref::testing::{allocated_bytes, CountingAllocator, LeakDetector};
+use cactusref::Rc;
+
+mod common;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+struct Node {
+    next: RefCell<Option<Rc<Node>>>,
+}
+
+fn ring(adopt: bool) {
+    let nodes = (0..10).map(|_| Node {
+        next: RefCell::new(None),
+    });
+    let set_next = |node: &Rc<Node>, next| *node.next.borrow_mut() = Some(next);
+    let nodes = if adopt {
+        common::ring(nodes, set_next)
+    } else {
+        common::unadopted_ring(nodes, set_next)
+    };
+    drop(nodes);
+}
+
+#[test]
+fn leak_detector() {
+    env_logger::Builder::from_env("CACTUS_LOG").init();
+
+    log::info!("leak detector with adopted ring");
+    LeakDetector::new("adopted ring").check(|| ring(true));
+
+    log::info!("leak detector with unadopted ring");
+    let before = allocated_bytes();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        LeakDetector::new("unadopted ring").check(|| ring(false));
+    }));
+    let message = result.unwrap_err();
+    let message = message.downcast_ref::<String>().unwrap();
+    assert!(message.starts_with("unadopted ring: leaked "));
+    assert!(allocated_bytes() > before);
+}
+
+#[test]
+fn leak_detector_tolerance() {
+    LeakDetector::new("tolerance")
+        .with_tolerance(64)
+        .check(|| std::mem::forget(Box::new([0_u8; 64])));
+}
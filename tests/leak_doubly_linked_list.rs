@@ -1,5 +1,6 @@
 #![warn(clippy::all)]
 #![warn(clippy::pedantic)]
+#![cfg(feature = "cycle-detection")]
 #![allow(clippy::shadow_unrelated)]
 
 use cactusref::{Adopt, Rc};
@@ -0,0 +1,120 @@
+#![warn(clippy::all)]
+#![warn(clippy::pedantic)]
+#![cfg(feature = "cycle-detection")]
+
+use std::cell::{Cell, RefCell};
+
+use cactusref::{Adopt, Rc, Weak};
+
+thread_local! {
+    static DROPS: Cell<usize> = const { Cell::new(0) };
+}
+
+struct Node {
+    links: RefCell<Vec<Rc<Node>>>,
+    peers: RefCell<Vec<Weak<Node>>>,
+}
+
+impl Drop for Node {
+    fn drop(&mut self) {
+        DROPS.with(|drops| drops.set(drops.get() + 1));
+        // Every member of a collected cycle is dead before any value is
+        // dropped, so no peer can be observed or resurrected.
+        for peer in self.peers.borrow().iter() {
+            assert_eq!(peer.strong_count(), 0);
+            assert!(peer.upgrade().is_none());
+        }
+    }
+}
+
+fn node() -> Rc<Node> {
+    Rc::new(Node {
+        links: RefCell::new(Vec::new()),
+        peers: RefCell::new(Vec::new()),
+    })
+}
+
+fn link(parent: &Rc<Node>, child: &Rc<Node>) {
+    let clone = Rc::clone(child);
+    unsafe {
+        Rc::adopt_unchecked(parent, &clone);
+    }
+    parent.links.borrow_mut().push(clone);
+}
+
+#[test]
+fn leak_imbalanced_cycle() {
+    env_logger::Builder::from_env("CACTUS_LOG").init();
+
+    log::info!("cycle where members have different in and out degrees");
+
+    // `a` has two outgoing adoptions and one incoming. `c` has two incoming
+    // adoptions and one outgoing.
+    //
+    //   a -> b -> c -> a
+    //   a ------> c
+    for _ in 0..256 {
+        let a = node();
+        let b = node();
+        let c = node();
+        link(&a, &b);
+        link(&b, &c);
+        link(&c, &a);
+        link(&a, &c);
+        let weaks = [&a, &b, &c].map(Rc::downgrade);
+        for node in [&a, &b, &c] {
+            node.peers.borrow_mut().extend(weaks.iter().cloned());
+        }
+
+        DROPS.with(|drops| drops.set(0));
+        drop(b);
+        drop(c);
+        drop(a);
+        assert_eq!(DROPS.with(Cell::get), 3);
+        for weak in &weaks {
+            assert!(weak.upgrade().is_none());
+            assert_eq!(weak.strong_count(), 0);
+            assert_eq!(weak.weak_count(), 0);
+        }
+    }
+}
+
+#[test]
+fn leak_imbalanced_cycle_with_dense_sinks() {
+    log::info!("cycle where several members have more incoming than outgoing adoptions");
+
+    // `c` and `d` each have three incoming adoptions and two outgoing.
+    for _ in 0..256 {
+        let a = node();
+        let b = node();
+        let c = node();
+        let d = node();
+        for (parent, child) in [
+            (&a, &c),
+            (&a, &d),
+            (&b, &c),
+            (&b, &d),
+            (&c, &d),
+            (&d, &c),
+            (&c, &a),
+            (&d, &b),
+        ] {
+            link(parent, child);
+        }
+        let weaks = [&a, &b, &c, &d].map(Rc::downgrade);
+        for node in [&a, &b, &c, &d] {
+            node.peers.borrow_mut().extend(weaks.iter().cloned());
+        }
+
+        DROPS.with(|drops| drops.set(0));
+        drop(a);
+        drop(b);
+        drop(c);
+        drop(d);
+        assert_eq!(DROPS.with(Cell::get), 4);
+        for weak in &weaks {
+            assert!(weak.upgrade().is_none());
+            assert_eq!(weak.strong_count(), 0);
+        }
+    }
+}
@@ -0,0 +1,79 @@
+#![warn(clippy::all)]
+#![warn(clippy::pedantic)]
+#![cfg(feature = "cycle-detection")]
+
+use std::cell::RefCell;
+
+use cactusref::{Rc, Weak};
+
+mod common;
+
+struct Node {
+    next: Option<Rc<RefCell<Node>>>,
+    weak_next: Weak<RefCell<Node>>,
+    weak_prev: Weak<RefCell<Node>>,
+    weak_self: Weak<RefCell<Node>>,
+}
+
+fn ring(len: usize) -> Vec<Rc<RefCell<Node>>> {
+    let nodes = (0..len).map(|_| {
+        RefCell::new(Node {
+            next: None,
+            weak_next: Weak::new(),
+            weak_prev: Weak::new(),
+            weak_self: Weak::new(),
+        })
+    });
+    let nodes = common::ring(nodes, |node, next| {
+        let mut inner = node.borrow_mut();
+        inner.weak_next = Rc::downgrade(&next);
+        inner.weak_self = Rc::downgrade(node);
+        inner.next = Some(next);
+    });
+    for (idx, node) in nodes.iter().enumerate() {
+        let prev = &nodes[(idx + len - 1) % len];
+        node.borrow_mut().weak_prev = Rc::downgrade(prev);
+    }
+    nodes
+}
+
+#[test]
+fn leak_ring_with_weak_neighbors() {
+    env_logger::Builder::from_env("CACTUS_LOG").init();
+
+    log::info!("ring where members hold weaks to each other");
+
+    let nodes = ring(10);
+    for node in &nodes {
+        // One weak from each neighbor and one from the node itself.
+        assert_eq!(Rc::weak_count(node), 3);
+    }
+    let weaks = nodes.iter().map(Rc::downgrade).collect::<Vec<_>>();
+    drop(nodes);
+
+    for weak in &weaks {
+        assert!(weak.upgrade().is_none());
+        assert_eq!(weak.strong_count(), 0);
+        assert_eq!(weak.weak_count(), 0);
+    }
+}
+
+#[test]
+fn leak_ring_with_only_internal_weaks() {
+    log::info!("ring where members hold weaks to each other with no external weaks");
+
+    // Every `Weak` is owned by a member of the cycle, so the allocations are
+    // freed by the cycle collector instead of by an external `Weak`.
+    let nodes = ring(10);
+    drop(nodes);
+}
+
+#[test]
+fn leak_pair_with_weak_neighbors() {
+    log::info!("two node ring where members hold weaks to each other");
+
+    let nodes = ring(2);
+    let weak = Rc::downgrade(&nodes[0]);
+    drop(nodes);
+    assert!(weak.upgrade().is_none());
+}
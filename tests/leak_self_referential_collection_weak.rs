@@ -1,5 +1,6 @@
 #![warn(clippy::all)]
 #![warn(clippy::pedantic)]
+#![cfg(feature = "cycle-detection")]
 
 use cactusref::{Adopt, Rc, Weak};
 use core::cell::RefCell;
@@ -44,3 +45,28 @@ fn leak_self_referential_collection_weak() {
     drop(borrow);
     drop(vec);
 }
+
+#[test]
+fn leak_self_referential_collection_self_weak() {
+    log::info!("self-referential collection self weak");
+
+    let s = "a".repeat(2 * 1024 * 1024);
+
+    let vec = Rc::new(RefCell::new(RArray {
+        inner: vec![],
+        alloc: s,
+    }));
+    let weak = Rc::downgrade(&vec);
+    for _ in 1..10 {
+        vec.borrow_mut().inner.push(Rc::self_weak(&vec));
+    }
+    assert_eq!(Rc::strong_count(&vec), 1);
+    assert_eq!(Rc::weak_count(&vec), 10);
+    assert!(vec
+        .borrow()
+        .inner
+        .iter()
+        .all(|elem| Rc::ptr_eq(&elem.upgrade().unwrap(), &vec)));
+    drop(vec);
+    assert!(weak.upgrade().is_none());
+}
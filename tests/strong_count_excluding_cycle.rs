@@ -0,0 +1,146 @@
+#![warn(clippy::all)]
+#![warn(clippy::pedantic)]
+#![cfg(feature = "cycle-detection")]
+
+use std::cell::RefCell;
+
+use cactusref::{Adopt, Rc};
+
+mod common;
+
+struct Node {
+    next: Option<Rc<RefCell<Node>>>,
+    data: i32,
+}
+
+#[test]
+fn strong_count_excluding_cycle_ring() {
+    env_logger::Builder::from_env("CACTUS_LOG").init();
+
+    log::info!("strong count excluding cycle");
+
+    let left = Node {
+        next: None,
+        data: 123,
+    };
+    let left = Rc::new(RefCell::new(left));
+
+    let right = Node {
+        next: Some(Rc::clone(&left)),
+        data: 456,
+    };
+    let right = Rc::new(RefCell::new(right));
+    unsafe {
+        Rc::adopt_unchecked(&right, &left);
+    }
+
+    left.borrow_mut().next = Some(Rc::clone(&right));
+    unsafe {
+        Rc::adopt_unchecked(&left, &right);
+    }
+
+    let node = Rc::clone(&left);
+    assert_eq!(node.borrow().data, 123);
+
+    // `left`, `node`, and the `next` pointer from `right`.
+    assert_eq!(Rc::strong_count(&left), 3);
+    assert_eq!(Rc::strong_count_excluding_cycle(&left), 2);
+    // `right` and the `next` pointer from `left`.
+    assert_eq!(Rc::strong_count(&right), 2);
+    assert_eq!(Rc::strong_count_excluding_cycle(&right), 1);
+
+    drop(node);
+    assert_eq!(Rc::strong_count(&left), 2);
+    assert_eq!(Rc::strong_count_excluding_cycle(&left), 1);
+
+    // Once `left` is dropped, only the cycle owns it.
+    let weak = Rc::downgrade(&left);
+    drop(left);
+    let left = weak.upgrade().unwrap();
+    assert_eq!(Rc::strong_count(&left), 2);
+    assert_eq!(Rc::strong_count_excluding_cycle(&left), 1);
+    drop(left);
+
+    drop(right);
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn strong_count_excluding_cycle_no_adoptions() {
+    let node = Rc::new(5);
+    let clone = Rc::clone(&node);
+    assert_eq!(Rc::strong_count_excluding_cycle(&node), 2);
+    drop(clone);
+    assert_eq!(Rc::strong_count_excluding_cycle(&node), 1);
+}
+
+#[test]
+fn strong_count_excluding_cycle_acyclic_parent() {
+    let child = Rc::new(RefCell::new(Node {
+        next: None,
+        data: 456,
+    }));
+    let parent = Rc::new(RefCell::new(Node {
+        next: Some(Rc::clone(&child)),
+        data: 123,
+    }));
+    unsafe {
+        Rc::adopt_unchecked(&parent, &child);
+    }
+    // The parent is not part of a cycle with the child, so its owned reference
+    // is external to any cycle.
+    assert_eq!(Rc::strong_count(&child), 2);
+    assert_eq!(Rc::strong_count_excluding_cycle(&child), 2);
+    assert_eq!(Rc::strong_count_excluding_cycle(&parent), 1);
+}
+
+fn ring(len: usize) -> Vec<Rc<RefCell<Node>>> {
+    let nodes = (0..len).map(|data| {
+        RefCell::new(Node {
+            next: None,
+            data: i32::try_from(data).unwrap(),
+        })
+    });
+    common::ring(nodes, |node, next| node.borrow_mut().next = Some(next))
+}
+
+#[test]
+fn is_cycle_root_ring_without_external_owners() {
+    let nodes = ring(4);
+    // Each node is owned by its predecessor and by the `Rc` in `nodes`, which
+    // is the `Rc` being inspected.
+    assert!(nodes.iter().all(|node| !Rc::is_cycle_root(node)));
+
+    let weak = Rc::downgrade(&nodes[0]);
+    drop(nodes);
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn is_cycle_root_ring_with_external_owners() {
+    let nodes = ring(4);
+    let root = Rc::clone(&nodes[2]);
+    let roots = nodes
+        .iter()
+        .filter(|node| Rc::is_cycle_root(node))
+        .map(|node| node.borrow().data)
+        .collect::<Vec<_>>();
+    assert_eq!(roots, [2]);
+    assert!(Rc::is_cycle_root(&root));
+
+    // Once the other `Rc`s are dropped, `root` is the only external owner.
+    let weak = Rc::downgrade(&root);
+    drop(nodes);
+    assert!(!Rc::is_cycle_root(&root));
+    assert!(weak.upgrade().is_some());
+    drop(root);
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn is_cycle_root_not_graphed() {
+    let node = Rc::new(5);
+    let clone = Rc::clone(&node);
+    assert!(!Rc::is_cycle_root(&node));
+    drop(clone);
+}
@@ -0,0 +1,65 @@
+#![warn(clippy::all)]
+#![warn(clippy::pedantic)]
+#![cfg(all(feature = "cycle-detection", feature = "std"))]
+
+use cactusref::{set_trace_budget, trace_budget, Rc};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::sync::Mutex;
+
+mod common;
+
+use common::node_ring;
+
+static LOGGER: CapturingLogger = CapturingLogger {
+    warnings: Mutex::new(Vec::new()),
+};
+
+struct CapturingLogger {
+    warnings: Mutex<Vec<String>>,
+}
+
+impl Log for CapturingLogger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.level() <= Level::Warn
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if self.enabled(record.metadata()) {
+            self.warnings
+                .lock()
+                .unwrap()
+                .push(record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+#[test]
+fn trace_budget_leaks_large_graph() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(LevelFilter::Warn);
+
+    assert_eq!(trace_budget(), usize::MAX);
+
+    let nodes = node_ring(10_000);
+    let weak = Rc::downgrade(&nodes[0]);
+
+    set_trace_budget(100);
+    assert_eq!(trace_budget(), 100);
+    drop(nodes);
+
+    // The ring is orphaned, but too large to trace within the budget.
+    assert!(weak.upgrade().is_some());
+    let warnings = LOGGER.warnings.lock().unwrap().clone();
+    assert!(!warnings.is_empty());
+    assert!(warnings
+        .iter()
+        .all(|warning| warning.contains("exceeded trace budget of 100 nodes")));
+
+    // Raising the budget allows the next drop of a member to collect the ring.
+    set_trace_budget(usize::MAX);
+    let leaked = weak.upgrade().unwrap();
+    drop(leaked);
+    assert!(weak.upgrade().is_none());
+}
@@ -0,0 +1,61 @@
+#![warn(clippy::all)]
+#![warn(clippy::pedantic)]
+#![cfg(all(debug_assertions, feature = "cycle-detection"))]
+
+use std::panic::{self, AssertUnwindSafe};
+
+use cactusref::{Adopt, Rc};
+
+mod common;
+
+use common::{ring, Node};
+
+// Build a cycle of two nodes which own and adopt each other.
+fn pair() -> (Rc<Node>, Rc<Node>) {
+    let mut nodes = ring([Node::new(0), Node::new(1)], |node, next| {
+        *node.next.borrow_mut() = Some(next);
+    });
+    let second = nodes.pop().unwrap();
+    let first = nodes.pop().unwrap();
+    (first, second)
+}
+
+#[test]
+fn balanced_cycle_is_collected() {
+    env_logger::Builder::from_env("CACTUS_LOG").init();
+
+    log::info!("balanced adoptions in an orphaned cycle");
+
+    let (first, second) = pair();
+    let weak = Rc::downgrade(&first);
+    drop(first);
+    drop(second);
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn unbalanced_cycle_reports_adoption_pair() {
+    log::info!("unbalanced adoptions in an orphaned cycle");
+
+    let (first, second) = pair();
+    // This adoption is not backed by a strong reference owned by `first`.
+    unsafe {
+        Rc::adopt_unchecked(&first, &second);
+    }
+    let from = format!("{:#x}", Rc::as_ptr(&first) as usize);
+    let to = format!("{:#x}", Rc::as_ptr(&second) as usize);
+
+    // `first` owns the only strong reference to `second`, so the cycle is
+    // orphaned once `first` is dropped.
+    drop(second);
+    let panic = panic::catch_unwind(AssertUnwindSafe(|| drop(first))).unwrap_err();
+    let message = panic.downcast_ref::<String>().unwrap();
+    assert!(message.contains("unbalanced adoptions"), "{message}");
+    assert!(
+        message.contains(&format!(
+            "{to} is adopted 2 times but has 1 strong references; adopted by {from} (2 times)"
+        )),
+        "{message}"
+    );
+    // The check runs before the cycle is torn down, so the cycle is leaked.
+}
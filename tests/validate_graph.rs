@@ -0,0 +1,95 @@
+#![warn(clippy::all)]
+#![warn(clippy::pedantic)]
+#![cfg(all(debug_assertions, feature = "cycle-detection"))]
+
+use std::cell::RefCell;
+
+use cactusref::{Adopt, Rc, Weak};
+
+const NODES: usize = 8;
+const OPERATIONS: usize = 500;
+
+struct Node {
+    children: RefCell<Vec<Rc<Node>>>,
+}
+
+// A xorshift generator keeps the test deterministic without a dependency.
+struct Rng(u64);
+
+impl Rng {
+    fn below(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        usize::try_from(self.0 % n as u64).unwrap()
+    }
+}
+
+fn validate_all(handles: &[Option<Rc<Node>>]) {
+    for handle in handles.iter().flatten() {
+        if let Err(err) = Rc::validate_graph(handle) {
+            panic!("graph invariant violated: {err}");
+        }
+    }
+}
+
+fn run(seed: u64) {
+    let mut rng = Rng(seed);
+    let mut handles = (0..NODES)
+        .map(|_| {
+            Some(Rc::new(Node {
+                children: RefCell::new(Vec::new()),
+            }))
+        })
+        .collect::<Vec<_>>();
+    let weaks = handles
+        .iter()
+        .flatten()
+        .map(Rc::downgrade)
+        .collect::<Vec<Weak<Node>>>();
+
+    for _ in 0..OPERATIONS {
+        match rng.below(10) {
+            // Adopt a random node reachable through a weak pointer.
+            0..=5 => {
+                let Some(parent) = &handles[rng.below(NODES)] else {
+                    continue;
+                };
+                let Some(child) = weaks[rng.below(NODES)].upgrade() else {
+                    continue;
+                };
+                let child = unsafe { Rc::adopt_and_keep(parent, &child) };
+                parent.children.borrow_mut().push(child);
+            }
+            // Release an adopted child.
+            6..=8 => {
+                let Some(parent) = &handles[rng.below(NODES)] else {
+                    continue;
+                };
+                let child = parent.children.borrow_mut().pop();
+                if let Some(child) = child {
+                    Rc::unadopt(parent, &child);
+                    drop(child);
+                }
+            }
+            // Drop a handle, which may collect a cycle.
+            _ => {
+                handles[rng.below(NODES)] = None;
+            }
+        }
+        validate_all(&handles);
+    }
+
+    drop(handles);
+    assert!(weaks.iter().all(|weak| weak.upgrade().is_none()));
+}
+
+#[test]
+fn validate_graph_after_random_operations() {
+    env_logger::Builder::from_env("CACTUS_LOG").init();
+
+    for seed in 1..=64 {
+        log::info!("random graph operations with seed {seed}");
+        run(seed);
+    }
+}
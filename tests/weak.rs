@@ -1,9 +1,14 @@
 #![warn(clippy::all)]
 #![warn(clippy::pedantic)]
+#![cfg(feature = "cycle-detection")]
 
-use cactusref::{Adopt, Rc};
+use cactusref::{retain_live, Adopt, Rc, Weak, WeakCounts};
 use std::cell::RefCell;
 
+mod common;
+
+use common::{node_ring, Node};
+
 #[derive(Default)]
 struct Array {
     buffer: Vec<Rc<RefCell<Self>>>,
@@ -42,3 +47,126 @@ fn weak() {
     assert_eq!(weak.weak_count(), 0);
     assert!(weak.upgrade().is_none());
 }
+
+#[test]
+fn weak_count_detailed() {
+    let array = Rc::new(RefCell::new(Array::default()));
+    for _ in 0..10 {
+        let item = Rc::clone(&array);
+        unsafe {
+            Rc::adopt_unchecked(&array, &item);
+        }
+        array.borrow_mut().buffer.push(item);
+    }
+    assert_eq!(
+        Rc::weak_count_detailed(&array),
+        WeakCounts {
+            external: 0,
+            total: 1,
+        }
+    );
+
+    let weak = Rc::downgrade(&array);
+    assert_eq!(
+        Rc::weak_count_detailed(&array),
+        WeakCounts {
+            external: 1,
+            total: 2,
+        }
+    );
+    assert_eq!(
+        weak.upgrade().as_ref().map(Rc::weak_count_detailed),
+        Some(WeakCounts {
+            external: 1,
+            total: 2,
+        })
+    );
+    assert_eq!(
+        Rc::weak_count(&array),
+        Rc::weak_count_detailed(&array).external
+    );
+
+    drop(array);
+
+    assert_eq!(weak.strong_count(), 0);
+    assert_eq!(weak.weak_count(), 0);
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn collected_ring_weak_counts() {
+    let nodes = node_ring(5);
+    let weak = Rc::downgrade(&nodes[2]);
+    let also_weak = Weak::clone(&weak);
+    assert_eq!(weak.strong_count(), 2);
+    assert_eq!(weak.weak_count(), 2);
+
+    drop(nodes);
+    assert!(weak.upgrade().is_none());
+    assert_eq!(weak.strong_count(), 0);
+    // Like `std::rc::Weak`, `weak_count` is 0 once there are no strong
+    // pointers, even though both weaks keep the allocation alive.
+    assert_eq!(weak.weak_count(), 0);
+
+    drop(also_weak);
+    assert!(weak.upgrade().is_none());
+    assert_eq!(weak.strong_count(), 0);
+    assert_eq!(weak.weak_count(), 0);
+}
+
+#[test]
+fn with_upgraded_sums_weak_collection() {
+    let values = (1..=5).map(Rc::new).collect::<Vec<_>>();
+    let mut weaks = values.iter().map(Rc::downgrade).collect::<Vec<Weak<i32>>>();
+    weaks.push(Weak::new());
+
+    let sum = weaks
+        .iter()
+        .filter_map(|weak| weak.with_upgraded(|value| *value))
+        .sum::<i32>();
+    assert_eq!(sum, 15);
+    assert!(values.iter().all(|value| Rc::strong_count(value) == 1));
+
+    drop(values);
+    assert!(weaks
+        .iter()
+        .all(|weak| weak.with_upgraded(|value| *value).is_none()));
+}
+
+#[test]
+fn retain_live_prunes_collected_cycle() {
+    let ring = node_ring(4);
+    let live = (0..3)
+        .map(|label| Rc::new(Node::new(label)))
+        .collect::<Vec<_>>();
+
+    let mut weaks = ring
+        .iter()
+        .zip(&live)
+        .flat_map(|(dead, live)| [Rc::downgrade(dead), Rc::downgrade(live)])
+        .collect::<Vec<_>>();
+    weaks.push(Weak::clone(&weaks[1]));
+    weaks.push(Weak::new());
+    assert_eq!(weaks.len(), 8);
+    assert_eq!(Rc::weak_count(&live[0]), 2);
+
+    drop(ring);
+    retain_live(&mut weaks);
+
+    assert_eq!(weaks.len(), 4);
+    assert!(weaks[0].points_to(&live[0]));
+    assert!(weaks[1].points_to(&live[1]));
+    assert!(weaks[2].points_to(&live[2]));
+    assert!(weaks[3].points_to(&live[0]));
+    assert_eq!(Rc::weak_count(&live[0]), 2);
+    assert_eq!(Rc::weak_count(&live[1]), 1);
+    assert_eq!(Rc::weak_count(&live[2]), 1);
+
+    // Pruning is idempotent.
+    retain_live(&mut weaks);
+    assert_eq!(weaks.len(), 4);
+
+    drop(live);
+    retain_live(&mut weaks);
+    assert!(weaks.is_empty());
+}